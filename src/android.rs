@@ -0,0 +1,157 @@
+//! Android `fonts.xml` parsing.
+//!
+//! Supports both the modern `fonts.xml`/`font_fallback.xml` format (Android 5.0+)
+//! and the legacy `system_fonts.xml`/`fallback_fonts.xml` one.
+
+use std::path::PathBuf;
+
+use crate::Style;
+
+/// A font file declared in `fonts.xml`.
+#[derive(Clone, Debug)]
+pub(crate) struct Font {
+    /// A font path relative to the fonts directory.
+    pub path: PathBuf,
+    pub index: u32,
+    pub weight: Option<u16>,
+    pub style: Option<Style>,
+}
+
+/// A font family declared in `fonts.xml`.
+#[derive(Clone, Debug)]
+pub(crate) struct Family {
+    /// A family name, like `sans-serif`. Fallback families usually don't have one.
+    pub name: Option<String>,
+    /// A fallback family language, like `und-Arab`.
+    pub lang: Option<String>,
+    pub fonts: Vec<Font>,
+}
+
+/// A family alias declared in `fonts.xml`.
+#[derive(Clone, Debug)]
+pub(crate) struct Alias {
+    pub name: String,
+    pub to: String,
+    pub weight: Option<u16>,
+}
+
+/// A parsed `fonts.xml` file.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Config {
+    pub families: Vec<Family>,
+    pub aliases: Vec<Alias>,
+}
+
+/// Parses an Android `fonts.xml` file.
+pub(crate) fn parse(text: &str) -> Result<Config, roxmltree::Error> {
+    let doc = roxmltree::Document::parse(text)?;
+
+    let mut config = Config::default();
+    for node in doc.root_element().children().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "family" => config.families.push(parse_family(node)),
+            "alias" => {
+                if let (Some(name), Some(to)) = (node.attribute("name"), node.attribute("to")) {
+                    config.aliases.push(Alias {
+                        name: name.to_string(),
+                        to: to.to_string(),
+                        weight: node.attribute("weight").and_then(|w| w.parse().ok()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(config)
+}
+
+fn parse_family(node: roxmltree::Node) -> Family {
+    let mut family = Family {
+        name: node.attribute("name").map(ToString::to_string),
+        lang: node.attribute("lang").map(ToString::to_string),
+        fonts: Vec::new(),
+    };
+
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "font" => {
+                if let Some(path) = font_path(child) {
+                    family.fonts.push(Font {
+                        path,
+                        index: child
+                            .attribute("index")
+                            .and_then(|i| i.parse().ok())
+                            .unwrap_or(0),
+                        weight: child.attribute("weight").and_then(|w| w.parse().ok()),
+                        style: match child.attribute("style") {
+                            Some("normal") => Some(Style::Normal),
+                            Some("italic") => Some(Style::Italic),
+                            _ => None,
+                        },
+                    });
+                }
+            }
+            // Legacy format.
+            "nameset" if family.name.is_none() => {
+                family.name = child
+                    .children()
+                    .find(|n| n.has_tag_name("name"))
+                    .and_then(|n| n.text())
+                    .map(|name| name.trim().to_string());
+            }
+            "fileset" => {
+                for file in child.children().filter(|n| n.has_tag_name("file")) {
+                    if family.lang.is_none() {
+                        family.lang = file.attribute("lang").map(ToString::to_string);
+                    }
+
+                    if let Some(path) = font_path(file) {
+                        family.fonts.push(Font {
+                            path,
+                            index: 0,
+                            weight: None,
+                            style: None,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    family
+}
+
+/// Checks that a space-separated list of language tags, like `ja ja-Latn`, matches a language.
+///
+/// Tags match when one is equal to the other or is its prefix, like `zh` and `zh-Hans`.
+pub(crate) fn lang_matches(tags: &str, lang: &str) -> bool {
+    tags.split_whitespace().any(|tag| {
+        let (short, long) = if tag.len() <= lang.len() {
+            (tag, lang)
+        } else {
+            (lang, tag)
+        };
+
+        long.get(..short.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(short))
+            && (long.len() == short.len() || long.as_bytes()[short.len()] == b'-')
+    })
+}
+
+/// Returns a font path, which is stored as a text before any `axis` elements.
+fn font_path(node: roxmltree::Node) -> Option<PathBuf> {
+    let text: String = node
+        .children()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(text))
+    }
+}
@@ -0,0 +1,275 @@
+//! A minimal zip and tar archives reader.
+//!
+//! Supports only what is needed to extract font files: stored and deflated zip entries
+//! and regular tar files, including GNU and PAX long names.
+
+use std::borrow::Cow;
+
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_OF_CENTRAL_DIR: u32 = 0x06054b50;
+
+const TAR_BLOCK: usize = 512;
+
+/// An archive entry.
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    /// A member path inside the archive.
+    pub name: String,
+    location: Location,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Location {
+    Zip {
+        local_header: usize,
+        method: u16,
+        compressed_size: usize,
+        size: usize,
+    },
+    Tar {
+        header: usize,
+        size: usize,
+    },
+}
+
+impl Entry {
+    /// Returns the entry header offset inside the archive.
+    ///
+    /// Unlike names, offsets are unique, so they can be used to find an entry again.
+    pub fn offset(&self) -> usize {
+        match self.location {
+            Location::Zip { local_header, .. } => local_header,
+            Location::Tar { header, .. } => header,
+        }
+    }
+
+    /// Returns the entry data, decompressing it when needed.
+    pub fn data<'a>(&self, archive: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+        match self.location {
+            Location::Zip {
+                local_header,
+                method,
+                compressed_size,
+                size,
+            } => {
+                let header = archive.get(local_header..local_header + 30)?;
+                if read_u32(header, 0)? != ZIP_LOCAL_HEADER {
+                    return None;
+                }
+
+                let name_len = read_u16(header, 26)? as usize;
+                let extra_len = read_u16(header, 28)? as usize;
+                let start = local_header + 30 + name_len + extra_len;
+                let data = archive.get(start..start.checked_add(compressed_size)?)?;
+                match method {
+                    0 => Some(Cow::Borrowed(data)),
+                    8 => {
+                        let data =
+                            miniz_oxide::inflate::decompress_to_vec_with_limit(data, size).ok()?;
+                        Some(Cow::Owned(data))
+                    }
+                    _ => None,
+                }
+            }
+            Location::Tar { header, size } => {
+                let start = header + TAR_BLOCK;
+                archive
+                    .get(start..start.checked_add(size)?)
+                    .map(Cow::Borrowed)
+            }
+        }
+    }
+}
+
+fn is_tar(data: &[u8]) -> bool {
+    // Both POSIX `ustar\0` and GNU `ustar  \0`.
+    data.get(257..262) == Some(b"ustar")
+}
+
+/// Returns regular file entries of an archive.
+///
+/// Returns `None` for unsupported or malformed archives.
+pub(crate) fn entries(data: &[u8]) -> Option<Vec<Entry>> {
+    if is_tar(data) {
+        tar_entries(data)
+    } else if data.starts_with(b"PK") {
+        zip_entries(data)
+    } else {
+        None
+    }
+}
+
+/// Finds an archive entry by its header offset. See [`Entry::offset`].
+///
+/// The entry name is not restored.
+pub(crate) fn entry_at(data: &[u8], offset: usize) -> Option<Entry> {
+    if is_tar(data) {
+        // Tar headers contain everything needed to locate the data.
+        let header = data.get(offset..offset.checked_add(TAR_BLOCK)?)?;
+        if !is_tar_file(header[156]) {
+            return None;
+        }
+
+        Some(Entry {
+            name: String::new(),
+            location: Location::Tar {
+                header: offset,
+                size: parse_octal(&header[124..136])?,
+            },
+        })
+    } else if data.starts_with(b"PK") {
+        // Zip local headers may lack sizes, so use the central directory instead.
+        zip_entries(data)?
+            .into_iter()
+            .find(|entry| entry.offset() == offset)
+    } else {
+        None
+    }
+}
+
+fn zip_entries(data: &[u8]) -> Option<Vec<Entry>> {
+    // The end of central directory record is followed by an up to 64KiB comment.
+    let min_start = data.len().saturating_sub(22 + 0xFFFF);
+    let end = (min_start..=data.len().checked_sub(22)?)
+        .rev()
+        .find(|i| read_u32(data, *i) == Some(ZIP_END_OF_CENTRAL_DIR))?;
+
+    let count = read_u16(data, end + 10)? as usize;
+    let mut offset = read_u32(data, end + 16)? as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        if read_u32(data, offset)? != ZIP_CENTRAL_HEADER {
+            return None;
+        }
+
+        let flags = read_u16(data, offset + 8)?;
+        let method = read_u16(data, offset + 10)?;
+        let compressed_size = read_u32(data, offset + 20)?;
+        let size = read_u32(data, offset + 24)?;
+        let name_len = read_u16(data, offset + 28)? as usize;
+        let extra_len = read_u16(data, offset + 30)? as usize;
+        let comment_len = read_u16(data, offset + 32)? as usize;
+        let local_header = read_u32(data, offset + 42)?;
+        let name = data.get(offset + 46..offset + 46 + name_len)?;
+        offset += 46 + name_len + extra_len + comment_len;
+
+        // Skip directories, encrypted and ZIP64 entries.
+        let is_zip64 = [compressed_size, size, local_header].contains(&u32::MAX);
+        if name.ends_with(b"/") || flags & 1 != 0 || is_zip64 {
+            continue;
+        }
+
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            location: Location::Zip {
+                local_header: local_header as usize,
+                method,
+                compressed_size: compressed_size as usize,
+                size: size as usize,
+            },
+        });
+    }
+
+    Some(entries)
+}
+
+fn tar_entries(data: &[u8]) -> Option<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    let mut long_name = None;
+    while let Some(header) = data.get(offset..offset + TAR_BLOCK) {
+        // An archive ends with zero blocks.
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let size = parse_octal(&header[124..136])?;
+        let content = offset + TAR_BLOCK;
+        let content_data = data.get(content..content.checked_add(size)?)?;
+        offset = content + (size + TAR_BLOCK - 1) / TAR_BLOCK * TAR_BLOCK;
+
+        match header[156] {
+            // A GNU long name of the next entry.
+            b'L' => long_name = Some(c_str(content_data).into_owned()),
+            // PAX extended headers of the next entry.
+            b'x' => long_name = pax_path(content_data).or(long_name),
+            kind if is_tar_file(kind) => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => {
+                        let name = c_str(&header[0..100]);
+                        let prefix = c_str(&header[345..500]);
+                        if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                            format!("{}/{}", prefix, name)
+                        } else {
+                            name.into_owned()
+                        }
+                    }
+                };
+
+                entries.push(Entry {
+                    name,
+                    location: Location::Tar {
+                        header: content - TAR_BLOCK,
+                        size,
+                    },
+                });
+            }
+            _ => long_name = None,
+        }
+    }
+
+    Some(entries)
+}
+
+/// Checks that a tar entry type is a regular file.
+fn is_tar_file(kind: u8) -> bool {
+    matches!(kind, b'0' | b'\0' | b'7')
+}
+
+/// Extracts a `path` record from PAX extended headers.
+///
+/// Records have the `<length> <key>=<value>\n` format.
+fn pax_path(mut data: &[u8]) -> Option<String> {
+    let mut path = None;
+    while !data.is_empty() {
+        let space = data.iter().position(|b| *b == b' ')?;
+        let len: usize = std::str::from_utf8(&data[..space]).ok()?.parse().ok()?;
+        let record = data.get(space + 1..len)?;
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some(value) = record.strip_prefix(b"path=") {
+            path = Some(String::from_utf8_lossy(value).into_owned());
+        }
+
+        data = &data[len..];
+    }
+
+    path
+}
+
+fn c_str(data: &[u8]) -> Cow<'_, str> {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end])
+}
+
+fn parse_octal(data: &[u8]) -> Option<usize> {
+    let text = c_str(data);
+    let text = text.trim_matches(|c: char| c == ' ' || c == '\0');
+    if text.is_empty() {
+        return Some(0);
+    }
+
+    usize::from_str_radix(text, 8).ok()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
@@ -0,0 +1,318 @@
+//! A persistent font faces cache.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::scan::FileStamp;
+use crate::{FaceFormat, FaceInfo, Language, Source, Stretch, Style, Weight, ID};
+
+const MAGIC: &[u8; 8] = b"FONTDBC\0";
+/// Must be incremented on any format change.
+const FORMAT_VERSION: u32 = 3;
+
+/// Font faces parsed from font files, keyed by a file path, size and modification time.
+#[derive(Clone, Debug)]
+pub(crate) struct FaceCache {
+    path: PathBuf,
+    files: HashMap<PathBuf, CachedFile>,
+    dirty: bool,
+}
+
+#[derive(Clone, Debug)]
+struct CachedFile {
+    stamp: FileStamp,
+    faces: Vec<FaceInfo>,
+    /// Whether the file was requested or updated during this session.
+    used: bool,
+}
+
+impl FaceCache {
+    /// Opens a cache stored in the specified directory.
+    ///
+    /// Each format and crate version uses its own cache file, so different `fontdb`
+    /// versions sharing a cache directory would not overwrite each other's caches.
+    /// A missing or malformed cache file is ignored.
+    pub fn open(dir: &Path) -> Self {
+        let name = format!(
+            "faces-{}-{}.cache",
+            FORMAT_VERSION,
+            env!("CARGO_PKG_VERSION")
+        );
+        let path = dir.join(name);
+        let files = std::fs::read(&path)
+            .ok()
+            .and_then(|data| parse(&data))
+            .unwrap_or_default();
+
+        FaceCache {
+            path,
+            files,
+            dirty: false,
+        }
+    }
+
+    /// Returns a default cache directory.
+    pub fn default_dir() -> Option<PathBuf> {
+        let env_dir = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+
+        let dir = if cfg!(target_os = "windows") {
+            env_dir("LOCALAPPDATA").map(PathBuf::from)
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            env_dir("HOME").map(|home| Path::new(&home).join("Library/Caches"))
+        } else {
+            env_dir("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env_dir("HOME").map(|home| Path::new(&home).join(".cache")))
+        };
+
+        dir.map(|dir| dir.join("fontdb"))
+    }
+
+    /// Returns cached faces of an unchanged file.
+    pub fn get(&mut self, path: &Path, stamp: FileStamp) -> Option<Vec<FaceInfo>> {
+        let file = self.files.get_mut(path)?;
+        if file.stamp != stamp {
+            return None;
+        }
+
+        file.used = true;
+        Some(file.faces.clone())
+    }
+
+    pub fn insert(&mut self, path: &Path, stamp: FileStamp, faces: Vec<FaceInfo>) {
+        let file = CachedFile {
+            stamp,
+            faces,
+            used: true,
+        };
+        self.files.insert(path.to_path_buf(), file);
+        self.dirty = true;
+    }
+
+    /// Writes the cache to the disk, when it was changed.
+    ///
+    /// Files that were not used during this session and no longer exist are removed.
+    pub fn save(&mut self) -> Result<(), std::io::Error> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        self.files.retain(|path, file| file.used || path.exists());
+
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        write_u32(&mut data, FORMAT_VERSION);
+        write_str(&mut data, env!("CARGO_PKG_VERSION"));
+
+        let files: Vec<_> = self
+            .files
+            .iter()
+            .filter_map(|(path, file)| Some((path_to_str(path)?, file)))
+            .collect();
+        write_u32(&mut data, files.len() as u32);
+        for (path, file) in files {
+            write_str(&mut data, path);
+            write_file(&mut data, file);
+        }
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        // Write to a temporary file first, so other processes would never see a partial cache.
+        let tmp_path = self
+            .path
+            .with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn write_file(data: &mut Vec<u8>, file: &CachedFile) {
+    data.extend_from_slice(&file.stamp.len.to_le_bytes());
+    match file
+        .stamp
+        .modified
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+    {
+        Some(time) => {
+            data.push(1);
+            data.extend_from_slice(&time.as_secs().to_le_bytes());
+            write_u32(data, time.subsec_nanos());
+        }
+        None => data.push(0),
+    }
+
+    write_u32(data, file.faces.len() as u32);
+    for face in &file.faces {
+        write_u32(data, face.index);
+        write_u32(data, face.families.len() as u32);
+        for (name, language) in &face.families {
+            write_str(data, name);
+            data.extend_from_slice(&language.windows_id().to_le_bytes());
+        }
+        write_str(data, &face.post_script_name);
+        data.push(match face.style {
+            Style::Normal => 0,
+            Style::Italic => 1,
+            Style::Oblique => 2,
+        });
+        data.extend_from_slice(&face.weight.0.to_le_bytes());
+        data.extend_from_slice(&face.stretch.to_number().to_le_bytes());
+        data.push(face.monospaced as u8);
+        // Zero is not a valid pixel size, so it's used for scalable fonts.
+        data.extend_from_slice(&face.pixel_size.unwrap_or(0).to_le_bytes());
+        data.push(match face.format {
+            FaceFormat::Sfnt => 0,
+            FaceFormat::Type1 => 1,
+            FaceFormat::Pcf => 2,
+            FaceFormat::Bdf => 3,
+            FaceFormat::WinFnt => 4,
+        });
+    }
+}
+
+fn write_u32(data: &mut Vec<u8>, n: u32) {
+    data.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_str(data: &mut Vec<u8>, s: &str) {
+    write_u32(data, s.len() as u32);
+    data.extend_from_slice(s.as_bytes());
+}
+
+/// Paths that are not valid UTF-8 are not cached.
+fn path_to_str(path: &Path) -> Option<&str> {
+    path.to_str()
+}
+
+fn parse(data: &[u8]) -> Option<HashMap<PathBuf, CachedFile>> {
+    let mut s = Reader { data };
+    if s.read_bytes(MAGIC.len())? != MAGIC
+        || s.read_u32()? != FORMAT_VERSION
+        || s.read_str()? != env!("CARGO_PKG_VERSION")
+    {
+        return None;
+    }
+
+    let count = s.read_u32()?;
+    let mut files = HashMap::new();
+    for _ in 0..count {
+        let path = PathBuf::from(s.read_str()?);
+        let file = parse_file(&mut s, &path)?;
+        files.insert(path, file);
+    }
+
+    Some(files)
+}
+
+fn parse_file(s: &mut Reader, path: &Path) -> Option<CachedFile> {
+    let len = s.read_u64()?;
+    let modified = match s.read_u8()? {
+        0 => None,
+        _ => {
+            let secs = s.read_u64()?;
+            let nanos = s.read_u32()?;
+            Some(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos))
+        }
+    };
+
+    let faces_count = s.read_u32()?;
+    let mut faces = Vec::new();
+    for _ in 0..faces_count {
+        let index = s.read_u32()?;
+        let families_count = s.read_u32()?;
+        let mut families = Vec::new();
+        for _ in 0..families_count {
+            let name = s.read_str()?.to_string();
+            let language = Language::windows_language(s.read_u16()?);
+            families.push((name, language));
+        }
+
+        let post_script_name = s.read_str()?.to_string();
+        let style = match s.read_u8()? {
+            0 => Style::Normal,
+            1 => Style::Italic,
+            _ => Style::Oblique,
+        };
+        let weight = Weight(s.read_u16()?);
+        let stretch = Stretch::from_number(s.read_u16()?);
+        let monospaced = s.read_u8()? != 0;
+        let pixel_size = Some(s.read_u16()?).filter(|size| *size != 0);
+        let format = match s.read_u8()? {
+            0 => FaceFormat::Sfnt,
+            1 => FaceFormat::Type1,
+            2 => FaceFormat::Pcf,
+            3 => FaceFormat::Bdf,
+            4 => FaceFormat::WinFnt,
+            _ => return None,
+        };
+
+        faces.push(FaceInfo {
+            id: ID::dummy(),
+            source: Source::File(path.to_path_buf()),
+            index,
+            families,
+            post_script_name,
+            style,
+            weight,
+            stretch,
+            monospaced,
+            pixel_size,
+            format,
+        });
+    }
+
+    Some(CachedFile {
+        stamp: FileStamp { len, modified },
+        faces,
+        used: false,
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        let mut bytes = [0; 2];
+        bytes.copy_from_slice(self.read_bytes(2)?);
+        Some(u16::from_le_bytes(bytes))
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.read_bytes(4)?);
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> Option<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    fn read_str(&mut self) -> Option<&'a str> {
+        let len = self.read_u32()? as usize;
+        core::str::from_utf8(self.read_bytes(len)?).ok()
+    }
+}
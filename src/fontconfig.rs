@@ -0,0 +1,795 @@
+//! fontconfig configuration and rules support.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use fontconfig_parser::{
+    Alias, ConfigPart, DirPrefix, EditMode, Expression, FontMatch, Match, MatchTarget, Property,
+    PropertyKind, SelectFont, TestCompare, TestQual, TestTarget, Value,
+};
+
+use crate::{FaceInfo, Style};
+
+/// A default configuration directory.
+const CONFIG_DIR: &str = "/etc/fonts";
+
+/// A fontconfig configuration used by
+/// [`Database::load_system_fonts`](crate::Database::load_system_fonts).
+///
+/// Can be retrieved via [`Database::system_font_config`](crate::Database::system_font_config).
+#[derive(Debug, Default)]
+pub struct SystemFontConfig {
+    /// Configuration files that were read, in the loading order.
+    pub files: Vec<PathBuf>,
+
+    /// Configuration files and directories that failed to load.
+    ///
+    /// Missing files included with `ignore_missing="yes"` are not reported.
+    pub errors: Vec<(PathBuf, std::io::Error)>,
+
+    /// Fonts directories that were scanned.
+    pub dirs: Vec<PathBuf>,
+
+    /// Families and their prioritized lists of substitutes from `<alias>` elements.
+    ///
+    /// Includes generic families, like `sans-serif`.
+    pub aliases: Vec<(String, Vec<String>)>,
+
+    /// Glob patterns of rejected font files from `<selectfont>` elements.
+    pub reject_globs: Vec<String>,
+
+    /// Glob patterns of accepted font files from `<selectfont>` elements.
+    ///
+    /// Accepted files are never rejected.
+    pub accept_globs: Vec<String>,
+
+    /// Patterns of rejected fonts from `<selectfont>` elements.
+    ///
+    /// Each pattern is a list of property names and values, like `("weight", "bold")`.
+    /// A font matches a pattern when it matches all of its properties.
+    /// Values that are not constants, like `<name>` or `<plus>` expressions, are empty.
+    pub reject_patterns: Vec<Vec<(String, String)>>,
+
+    /// Patterns of accepted fonts from `<selectfont>` elements.
+    ///
+    /// Accepted fonts are never rejected.
+    pub accept_patterns: Vec<Vec<(String, String)>>,
+}
+
+/// A parsed fontconfig configuration.
+#[derive(Debug, Default)]
+pub(crate) struct Config {
+    pub dirs: Vec<PathBuf>,
+    pub aliases: Vec<Alias>,
+    pub select_fonts: Vec<SelectFont>,
+    pub matches: Vec<Match>,
+    /// Configuration files that were read, in the loading order.
+    pub files: Vec<PathBuf>,
+    /// Configuration files that failed to load.
+    pub errors: Vec<(PathBuf, std::io::Error)>,
+}
+
+impl Config {
+    /// Loads the configuration the same way fontconfig does.
+    ///
+    /// Respects `FONTCONFIG_FILE`, `FONTCONFIG_PATH` and `FONTCONFIG_SYSROOT`
+    /// environment variables and follows `<include>` elements.
+    pub fn load() -> Self {
+        Self::load_with(&env_var)
+    }
+
+    /// Loads the configuration like [`Config::load`], but reads environment variables
+    /// using `var` instead.
+    pub fn load_with(var: &dyn Fn(&str) -> Option<OsString>) -> Self {
+        let env = Env::new(var);
+        let mut config = Config::default();
+        let mut seen = HashSet::new();
+
+        let file = var("FONTCONFIG_FILE").unwrap_or_else(|| "fonts.conf".into());
+        let file = Path::new(&file);
+        match env.find_config(file) {
+            Some(path) => config.include(&path, false, &env, &mut seen),
+            None => config.errors.push((file.to_path_buf(), not_found())),
+        }
+
+        config
+    }
+
+    fn include(
+        &mut self,
+        path: &Path,
+        ignore_missing: bool,
+        env: &Env,
+        seen: &mut HashSet<PathBuf>,
+    ) {
+        if let Err(e) = self.include_impl(path, env, seen) {
+            if !(ignore_missing && e.kind() == std::io::ErrorKind::NotFound) {
+                log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+                self.errors.push((path.to_path_buf(), e));
+            }
+        }
+    }
+
+    fn include_impl(
+        &mut self,
+        path: &Path,
+        env: &Env,
+        seen: &mut HashSet<PathBuf>,
+    ) -> Result<(), std::io::Error> {
+        if !seen.insert(std::fs::canonicalize(path)?) {
+            return Ok(());
+        }
+
+        if !std::fs::metadata(path)?.is_dir() {
+            return self.merge(path, env, seen);
+        }
+
+        // Like fontconfig, load only files like `10-name.conf`, in lexicographic order.
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(|c: char| c.is_ascii_digit()) && name.ends_with(".conf") {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+
+        for file in files {
+            self.include(&file, false, env, seen);
+        }
+
+        Ok(())
+    }
+
+    fn merge(
+        &mut self,
+        path: &Path,
+        env: &Env,
+        seen: &mut HashSet<PathBuf>,
+    ) -> Result<(), std::io::Error> {
+        let text = std::fs::read_to_string(path)?;
+        let parts = fontconfig_parser::parse_config_parts(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        self.files.push(path.to_path_buf());
+        for part in parts {
+            match part {
+                ConfigPart::Dir(dir) => {
+                    if let Some(dir) = env.resolve(&dir.path, dir.prefix, path, XdgDir::Data) {
+                        self.dirs.push(env.with_sysroot(&dir));
+                    }
+                }
+                ConfigPart::ResetDirs => self.dirs.clear(),
+                ConfigPart::Include(include) => {
+                    // Files without a prefix are looked up in configuration directories.
+                    let target = if include.prefix == DirPrefix::Default
+                        || include.path.starts_with('~')
+                        || Path::new(&include.path).is_absolute()
+                    {
+                        env.find_config(Path::new(&include.path))
+                    } else {
+                        env.resolve(&include.path, include.prefix, path, XdgDir::Config)
+                            .map(|p| env.with_sysroot(&p))
+                    };
+
+                    match target {
+                        Some(target) => self.include(&target, include.ignore_missing, env, seen),
+                        None if include.ignore_missing => {}
+                        None => self.errors.push((PathBuf::from(include.path), not_found())),
+                    }
+                }
+                ConfigPart::Alias(alias) => self.aliases.push(alias),
+                ConfigPart::SelectFont(select) => self.select_fonts.push(select),
+                ConfigPart::Match(m) => self.matches.push(m),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Converts the configuration into a public one, consuming loading results.
+    pub fn take_system_config(&mut self) -> SystemFontConfig {
+        let mut aliases: Vec<(String, Vec<String>)> = Vec::new();
+        for alias in &self.aliases {
+            let name = normalize_generic(&alias.alias);
+            if !aliases.iter().any(|(n, _)| normalize_generic(n) == name) {
+                let families = alias_families(&self.aliases, &name);
+                let name = if name == "sans-serif" {
+                    name
+                } else {
+                    alias.alias.trim().to_string()
+                };
+                aliases.push((name, families));
+            }
+        }
+
+        let select_fonts = &self.select_fonts;
+        let globs = |select: fn(&SelectFont) -> &Vec<FontMatch>| {
+            select_fonts
+                .iter()
+                .flat_map(select)
+                .filter_map(|rule| match rule {
+                    FontMatch::Glob(glob) => Some(glob.clone()),
+                    FontMatch::Pattern(_) => None,
+                })
+                .collect()
+        };
+        let patterns = |select: fn(&SelectFont) -> &Vec<FontMatch>| {
+            select_fonts
+                .iter()
+                .flat_map(select)
+                .filter_map(|rule| match rule {
+                    FontMatch::Glob(_) => None,
+                    FontMatch::Pattern(pattern) => {
+                        Some(pattern.iter().map(property_string).collect())
+                    }
+                })
+                .collect()
+        };
+
+        SystemFontConfig {
+            files: core::mem::take(&mut self.files),
+            errors: core::mem::take(&mut self.errors),
+            dirs: self.dirs.clone(),
+            aliases,
+            reject_globs: globs(|s| &s.rejects),
+            accept_globs: globs(|s| &s.accepts),
+            reject_patterns: patterns(|s| &s.rejects),
+            accept_patterns: patterns(|s| &s.accepts),
+        }
+    }
+}
+
+fn not_found() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::NotFound, "config file not found")
+}
+
+/// Environment used for paths resolving.
+struct Env {
+    home: Option<PathBuf>,
+    sysroot: Option<PathBuf>,
+    /// Configuration directories from `FONTCONFIG_PATH`, followed by the default one.
+    config_dirs: Vec<PathBuf>,
+    xdg_data_home: Option<PathBuf>,
+    xdg_config_home: Option<PathBuf>,
+}
+
+impl Env {
+    fn new(var: &dyn Fn(&str) -> Option<OsString>) -> Self {
+        let mut config_dirs: Vec<PathBuf> = match var("FONTCONFIG_PATH") {
+            Some(paths) => std::env::split_paths(&paths)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect(),
+            None => Vec::new(),
+        };
+        config_dirs.push(PathBuf::from(CONFIG_DIR));
+
+        Env {
+            home: var("HOME").map(PathBuf::from),
+            sysroot: var("FONTCONFIG_SYSROOT").map(PathBuf::from),
+            config_dirs,
+            xdg_data_home: var("XDG_DATA_HOME").map(PathBuf::from),
+            xdg_config_home: var("XDG_CONFIG_HOME").map(PathBuf::from),
+        }
+    }
+
+    /// Prepends `FONTCONFIG_SYSROOT` to an absolute path.
+    fn with_sysroot(&self, path: &Path) -> PathBuf {
+        match (&self.sysroot, path.strip_prefix("/")) {
+            (Some(sysroot), Ok(relative)) if !path.starts_with(sysroot) => sysroot.join(relative),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Finds an existing configuration file or directory, like `FcConfigGetFilename`.
+    fn find_config(&self, name: &Path) -> Option<PathBuf> {
+        if let Ok(relative) = name.strip_prefix("~") {
+            return self
+                .home
+                .as_ref()
+                .map(|home| home.join(relative))
+                .filter(|p| p.exists());
+        }
+
+        if name.is_absolute() {
+            return Some(self.with_sysroot(name)).filter(|p| p.exists());
+        }
+
+        self.config_dirs
+            .iter()
+            .map(|dir| self.with_sysroot(dir).join(name))
+            .find(|p| p.exists())
+    }
+
+    /// Resolves a `<dir>` or `<include>` path according to its prefix.
+    ///
+    /// Returns `None` when a path relies on a missing home directory.
+    fn resolve(
+        &self,
+        path: &str,
+        prefix: DirPrefix,
+        config_file: &Path,
+        xdg_dir: XdgDir,
+    ) -> Option<PathBuf> {
+        if let Ok(relative) = Path::new(path).strip_prefix("~") {
+            return self.home.as_ref().map(|home| home.join(relative));
+        }
+
+        if Path::new(path).is_absolute() {
+            return Some(PathBuf::from(path));
+        }
+
+        let base = match prefix {
+            DirPrefix::Xdg => match xdg_dir {
+                XdgDir::Data => match self.xdg_data_home {
+                    Some(ref dir) => dir.clone(),
+                    None => self.home.as_ref()?.join(".local/share"),
+                },
+                XdgDir::Config => match self.xdg_config_home {
+                    Some(ref dir) => dir.clone(),
+                    None => self.home.as_ref()?.join(".config"),
+                },
+            },
+            DirPrefix::Relative => config_file.parent()?.to_path_buf(),
+            DirPrefix::Default | DirPrefix::Cwd => std::env::current_dir().ok()?,
+        };
+
+        Some(base.join(path))
+    }
+}
+
+/// A base directory used by paths with the `xdg` prefix.
+#[derive(Clone, Copy)]
+enum XdgDir {
+    /// `XDG_DATA_HOME`, used by `<dir>`.
+    Data,
+    /// `XDG_CONFIG_HOME`, used by `<include>`.
+    Config,
+}
+
+/// Returns a non-empty environment variable.
+fn env_var(name: &str) -> Option<OsString> {
+    std::env::var_os(name).filter(|v| !v.is_empty())
+}
+
+/// Resolves a generic family into a prioritized list of families using `<alias>` elements.
+///
+/// Follows fontconfig's own order: all `prefer` families go first, in the declaration order,
+/// then `accept` families, where each later alias is inserted before the previous ones,
+/// and `default` families go last.
+pub(crate) fn alias_families(aliases: &[Alias], generic: &str) -> Vec<String> {
+    let aliases: Vec<_> = aliases
+        .iter()
+        .filter(|a| normalize_generic(&a.alias) == generic)
+        .collect();
+
+    let prefer = aliases.iter().flat_map(|a| a.prefer.iter());
+    let accept = aliases.iter().rev().flat_map(|a| a.accept.iter());
+    let default = aliases.iter().flat_map(|a| a.default.iter());
+
+    let mut families: Vec<String> = Vec::new();
+    for family in prefer.chain(accept).chain(default) {
+        if !families
+            .iter()
+            .any(|f| eq_ignore_blanks_and_case(f, family))
+        {
+            families.push(family.clone());
+        }
+    }
+
+    families
+}
+
+fn normalize_generic(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    if name == "sans serif" {
+        "sans-serif".to_string()
+    } else {
+        name
+    }
+}
+
+/// Font selection rules from `<selectfont>` elements.
+///
+/// Accept rules always take precedence over reject rules.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SelectRules {
+    accepts: Vec<FontMatch>,
+    rejects: Vec<FontMatch>,
+}
+
+impl SelectRules {
+    pub fn new(select_fonts: &[SelectFont]) -> Self {
+        let mut rules = SelectRules::default();
+        for select in select_fonts {
+            rules.accepts.extend(select.accepts.iter().cloned());
+            rules.rejects.extend(select.rejects.iter().cloned());
+        }
+
+        rules
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rejects.is_empty()
+    }
+
+    /// Checks that a font file is rejected by `<glob>` rules.
+    pub fn rejects_file(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        let matches = |rules: &[FontMatch]| {
+            rules.iter().any(|rule| match rule {
+                FontMatch::Glob(glob) => crate::glob::fc_glob_match(glob, &path),
+                FontMatch::Pattern(_) => false,
+            })
+        };
+
+        matches(&self.rejects) && !matches(&self.accepts)
+    }
+
+    /// Checks that a font face is rejected by `<pattern>` rules.
+    pub fn rejects_face(&self, face: &FaceInfo) -> bool {
+        let matches = |rules: &[FontMatch]| {
+            rules.iter().any(|rule| match rule {
+                FontMatch::Glob(_) => false,
+                FontMatch::Pattern(pattern) => pattern_matches(pattern, face),
+            })
+        };
+
+        matches(&self.rejects) && !matches(&self.accepts)
+    }
+}
+
+/// Family substitution rules from `<match target="pattern">` elements.
+///
+/// Only `family` and `lang` tests and `family` edits are supported.
+/// Rules with other tests are ignored, while other edits are skipped.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MatchRules {
+    rules: Vec<Match>,
+}
+
+impl MatchRules {
+    pub fn new(matches: &[Match]) -> Self {
+        let rules = matches
+            .iter()
+            .filter(|m| m.target == MatchTarget::Pattern)
+            .filter(|m| m.edits.iter().any(|e| family_value(&e.value).is_some()))
+            .filter(|m| m.tests.iter().all(is_supported_test))
+            .cloned()
+            .collect();
+
+        MatchRules { rules }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies rules to a prioritized list of requested families, like `FcConfigSubstitute`.
+    pub fn substitute(&self, families: &mut Vec<String>, lang: Option<&str>) {
+        for rule in &self.rules {
+            // An index of the family matched by the last `family` test.
+            let mut matched = None;
+            let passed = rule.tests.iter().all(|test| match test.value {
+                Property::Lang(Expression::Simple(Value::String(ref value))) => {
+                    let langs: &[&str] = match lang {
+                        Some(ref lang) => core::slice::from_ref(lang),
+                        None => &[],
+                    };
+                    test_values(test.qual, test.compare, langs, |lang| {
+                        compare_strings(test.compare, lang, value, eq_ignore_case)
+                    })
+                    .is_some()
+                }
+                Property::Family(Expression::Simple(Value::String(ref value))) => {
+                    let result = test_values(test.qual, test.compare, families, |family| {
+                        compare_strings(test.compare, family, value, eq_ignore_blanks_and_case)
+                    });
+                    if let Some(index) = result {
+                        matched = index;
+                    }
+                    result.is_some()
+                }
+                _ => false,
+            });
+
+            if !passed {
+                continue;
+            }
+
+            for edit in &rule.edits {
+                let value = match family_value(&edit.value) {
+                    Some(v) => v.to_string(),
+                    None => continue,
+                };
+
+                matched = apply_edit(families, edit.mode, matched, value);
+            }
+        }
+    }
+}
+
+fn is_supported_test(test: &fontconfig_parser::Test) -> bool {
+    if !matches!(test.target, TestTarget::Default | TestTarget::Pattern) {
+        return false;
+    }
+
+    matches!(
+        test.value,
+        Property::Family(Expression::Simple(Value::String(_)))
+            | Property::Lang(Expression::Simple(Value::String(_)))
+    )
+}
+
+fn family_value(property: &Property) -> Option<&str> {
+    match property {
+        Property::Family(Expression::Simple(Value::String(name))) => Some(name),
+        _ => None,
+    }
+}
+
+/// Tests pattern values.
+///
+/// Returns `None` when the test fails, otherwise an index of the first matched value.
+/// A missing element passes only `not_eq` tests with the `all` qualifier, like in fontconfig,
+/// but nothing is matched in this case.
+fn test_values<T>(
+    qual: TestQual,
+    compare: TestCompare,
+    values: &[T],
+    test: impl Fn(&T) -> bool,
+) -> Option<Option<usize>> {
+    if values.is_empty() {
+        return if qual == TestQual::All && compare == TestCompare::NotEq {
+            Some(None)
+        } else {
+            None
+        };
+    }
+
+    match qual {
+        TestQual::Any => values.iter().position(test).map(Some),
+        TestQual::All => values.iter().all(test).then_some(Some(0)),
+    }
+}
+
+fn compare_strings(
+    compare: TestCompare,
+    a: &str,
+    b: &str,
+    eq: impl Fn(&str, &str) -> bool,
+) -> bool {
+    match compare {
+        TestCompare::Eq => eq(a, b),
+        TestCompare::NotEq => !eq(a, b),
+        TestCompare::Contains => contains_ignore_case(a, b),
+        TestCompare::NotContains => !contains_ignore_case(a, b),
+        _ => false,
+    }
+}
+
+/// Applies a single edit and returns a new index of the matched family.
+///
+/// Without a matched family, edits are applied to the whole list.
+fn apply_edit(
+    families: &mut Vec<String>,
+    mode: EditMode,
+    matched: Option<usize>,
+    value: String,
+) -> Option<usize> {
+    // The matched family could have been removed by a previous edit.
+    let matched = matched.filter(|idx| *idx < families.len());
+    match (mode, matched) {
+        (EditMode::Assign, Some(idx)) => {
+            families[idx] = value;
+            Some(idx)
+        }
+        (EditMode::Assign, None) | (EditMode::AssignReplace, _) => {
+            families.clear();
+            families.push(value);
+            None
+        }
+        (EditMode::Prepend, Some(idx)) => {
+            families.insert(idx, value);
+            Some(idx + 1)
+        }
+        (EditMode::Prepend, None) | (EditMode::PrependFirst, _) => {
+            families.insert(0, value);
+            matched.map(|idx| idx + 1)
+        }
+        (EditMode::Append, Some(idx)) => {
+            families.insert(idx + 1, value);
+            Some(idx)
+        }
+        (EditMode::Append, None) | (EditMode::AppendLast, _) => {
+            families.push(value);
+            matched
+        }
+        (EditMode::Delete, Some(idx)) => {
+            families.remove(idx);
+            None
+        }
+        (EditMode::Delete, None) | (EditMode::DeleteAll, _) => {
+            families.clear();
+            None
+        }
+    }
+}
+
+/// Checks that all pattern elements match a font face.
+///
+/// Unsupported properties never match, so such patterns will not reject anything.
+fn pattern_matches(pattern: &[Property], face: &FaceInfo) -> bool {
+    !pattern.is_empty()
+        && pattern
+            .iter()
+            .all(|property| property_matches(property, face))
+}
+
+fn property_matches(property: &Property, face: &FaceInfo) -> bool {
+    let kind = property.kind();
+    let value = match property {
+        Property::Family(Expression::Simple(v))
+        | Property::Postscriptname(Expression::Simple(v))
+        | Property::File(Expression::Simple(v))
+        | Property::Index(Expression::Simple(v))
+        | Property::Slant(Expression::Simple(v))
+        | Property::Weight(Expression::Simple(v))
+        | Property::Width(Expression::Simple(v))
+        | Property::Spacing(Expression::Simple(v))
+        | Property::Scalable(Expression::Simple(v))
+        | Property::Outline(Expression::Simple(v)) => v,
+        _ => return false,
+    };
+
+    match kind {
+        PropertyKind::Family => match value {
+            Value::String(name) => face
+                .families
+                .iter()
+                .any(|(family, _)| eq_ignore_blanks_and_case(family, name)),
+            _ => false,
+        },
+        PropertyKind::Postscriptname => match value {
+            Value::String(name) => eq_ignore_blanks_and_case(&face.post_script_name, name),
+            _ => false,
+        },
+        PropertyKind::File => match (value, face.source.path()) {
+            (Value::String(file), Some(path)) => Path::new(file) == path,
+            _ => false,
+        },
+        PropertyKind::Scalable | PropertyKind::Outline => matches!(value, Value::Bool(true)),
+        PropertyKind::Index => int_value(value, kind) == Some(face.index),
+        PropertyKind::Slant => int_value(value, kind) == Some(slant(face.style)),
+        PropertyKind::Weight => int_value(value, kind) == Some(weight(face.weight.0)),
+        PropertyKind::Width => int_value(value, kind) == Some(width(face.stretch)),
+        PropertyKind::Spacing => face.monospaced && int_value(value, kind) == Some(100),
+        _ => false,
+    }
+}
+
+/// Converts a pattern element into a property name and value, like `("weight", "bold")`.
+fn property_string(property: &Property) -> (String, String) {
+    let name = match property.kind() {
+        PropertyKind::Dynamic(name) => name,
+        kind => format!("{:?}", kind).to_ascii_lowercase(),
+    };
+
+    let value = match property {
+        Property::Family(Expression::Simple(v))
+        | Property::Postscriptname(Expression::Simple(v))
+        | Property::File(Expression::Simple(v))
+        | Property::Index(Expression::Simple(v))
+        | Property::Slant(Expression::Simple(v))
+        | Property::Weight(Expression::Simple(v))
+        | Property::Width(Expression::Simple(v))
+        | Property::Spacing(Expression::Simple(v))
+        | Property::Scalable(Expression::Simple(v))
+        | Property::Outline(Expression::Simple(v))
+        | Property::Lang(Expression::Simple(v))
+        | Property::Dynamic(_, Expression::Simple(v)) => value_string(v),
+        _ => String::new(),
+    };
+
+    (name, value)
+}
+
+fn value_string(value: &Value) -> String {
+    match value {
+        Value::String(s) | Value::LangSet(s) => s.clone(),
+        Value::Int(n) => n.to_string(),
+        Value::Double(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Constant(c) => format!("{:?}", c).to_ascii_lowercase(),
+        _ => String::new(),
+    }
+}
+
+fn int_value(value: &Value, kind: PropertyKind) -> Option<u32> {
+    match value {
+        Value::Int(n) => Some(*n),
+        Value::Double(n) => Some(*n as u32),
+        Value::Constant(c) => c.get_value(kind),
+        _ => None,
+    }
+}
+
+/// Compares strings like fontconfig does for family names.
+fn eq_ignore_blanks_and_case(a: &str, b: &str) -> bool {
+    let a = a.chars().filter(|c| *c != ' ');
+    let b = b.chars().filter(|c| *c != ' ');
+    a.map(|c| c.to_ascii_lowercase())
+        .eq(b.map(|c| c.to_ascii_lowercase()))
+}
+
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .to_ascii_lowercase()
+        .contains(&needle.to_ascii_lowercase())
+}
+
+fn slant(style: Style) -> u32 {
+    match style {
+        Style::Normal => 0,
+        Style::Italic => 100,
+        Style::Oblique => 110,
+    }
+}
+
+/// Converts an OpenType weight into a fontconfig one.
+///
+/// Same as `FcWeightFromOpenTypeDouble`.
+fn weight(weight: u16) -> u32 {
+    const MAP: &[(f32, f32)] = &[
+        (0.0, 0.0),
+        (100.0, 0.0),
+        (200.0, 40.0),
+        (300.0, 50.0),
+        (350.0, 55.0),
+        (380.0, 75.0),
+        (400.0, 80.0),
+        (500.0, 100.0),
+        (600.0, 180.0),
+        (700.0, 200.0),
+        (800.0, 205.0),
+        (900.0, 210.0),
+        (1000.0, 215.0),
+    ];
+
+    let ot = f32::from(weight.min(1000));
+    for pair in MAP.windows(2) {
+        let (ot1, fc1) = pair[0];
+        let (ot2, fc2) = pair[1];
+        if ot <= ot2 {
+            return (fc1 + (fc2 - fc1) * (ot - ot1) / (ot2 - ot1)).round() as u32;
+        }
+    }
+
+    215
+}
+
+fn width(stretch: crate::Stretch) -> u32 {
+    match stretch.to_number() {
+        1 => 50,
+        2 => 63,
+        3 => 75,
+        4 => 87,
+        5 => 100,
+        6 => 113,
+        7 => 125,
+        8 => 150,
+        _ => 200,
+    }
+}
@@ -0,0 +1,142 @@
+//! A minimal glob patterns matcher.
+
+/// Checks that `text` matches a glob `pattern`.
+///
+/// Supports:
+///
+/// - `?` - any single character, except `/`
+/// - `*` - any sequence of characters, except `/`
+/// - `**` - any sequence of characters, including `/`.
+///   `**/` can match zero directories as well, so `**/a` matches `a`
+/// - `[abc]`, `[a-z]` and `[!a-z]` - a character class
+///
+/// Everything else is matched literally.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, &text, true)
+}
+
+/// Checks that `text` matches a fontconfig glob `pattern`.
+///
+/// Unlike [`glob_match`], `?` and `*` can match a `/` as well.
+#[cfg(all(
+    unix,
+    feature = "fontconfig",
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+pub(crate) fn fc_glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, &text, false)
+}
+
+fn match_from(pattern: &[char], text: &[char], literal_separator: bool) -> bool {
+    let mut p = 0;
+    let mut t = 0;
+    while p < pattern.len() {
+        match pattern[p] {
+            '*' => {
+                let double = pattern.get(p + 1) == Some(&'*');
+                let any_depth = double || !literal_separator;
+                let rest = if double { p + 2 } else { p + 1 };
+
+                // `**/` at a component start matches zero directories.
+                let at_component_start = t == 0 || text[t - 1] == '/';
+                if double
+                    && pattern.get(rest) == Some(&'/')
+                    && at_component_start
+                    && match_from(&pattern[rest + 1..], &text[t..], literal_separator)
+                {
+                    return true;
+                }
+
+                // Try all possible splits. Patterns are short, so this is fine.
+                for end in t..=text.len() {
+                    if match_from(&pattern[rest..], &text[end..], literal_separator) {
+                        return true;
+                    }
+
+                    if end < text.len() && text[end] == '/' && !any_depth {
+                        return false;
+                    }
+                }
+
+                return false;
+            }
+            '?' => {
+                if t >= text.len() || (literal_separator && text[t] == '/') {
+                    return false;
+                }
+            }
+            '[' => {
+                if t >= text.len() {
+                    return false;
+                }
+
+                match match_class(&pattern[p + 1..], text[t]) {
+                    Some((matched, len)) => {
+                        if !matched {
+                            return false;
+                        }
+
+                        p += len;
+                    }
+                    // An unclosed class is matched literally.
+                    None => {
+                        if text[t] != '[' {
+                            return false;
+                        }
+                    }
+                }
+            }
+            c => {
+                if t >= text.len() || text[t] != c {
+                    return false;
+                }
+            }
+        }
+
+        p += 1;
+        t += 1;
+    }
+
+    t == text.len()
+}
+
+/// Matches a character class, excluding the opening bracket.
+///
+/// Returns the match result and the class length, including the closing bracket.
+fn match_class(class: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 0;
+    let negated = matches!(class.first(), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    while i < class.len() {
+        let start = class[i];
+        if start == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+
+        first = false;
+        if class.get(i + 1) == Some(&'-') && class.get(i + 2).is_some_and(|end| *end != ']') {
+            if (start..=class[i + 2]).contains(&c) {
+                matched = true;
+            }
+
+            i += 3;
+        } else {
+            if start == c {
+                matched = true;
+            }
+
+            i += 1;
+        }
+    }
+
+    None
+}
@@ -4,6 +4,10 @@
 # Features
 
 - The database can load fonts from files, directories and raw data (`Vec<u8>`).
+- WOFF, WOFF2 and EOT fonts are unpacked transparently.
+- Mac `.dfont` suitcases are loaded as font collections.
+- PostScript Type 1, X11 (PCF/BDF) and Windows (FON/FNT) bitmap fonts metadata
+  can be parsed via optional features.
 - The database can match a font using CSS-like queries. See `Database::query`.
 - The database can try to load system fonts.
   Currently, this is implemented by scanning predefined directories.
@@ -22,7 +26,10 @@
 - Application's global database.<br>
   The database doesn't use `static`, therefore it's up to the caller where it should be stored.
 
-- Font types support other than TrueType.
+- Font types support other than TrueType, beyond storing and matching.<br>
+  WOFF/WOFF2, EOT and Mac `.dfont` suitcases are just TrueType containers.
+  PostScript Type 1, X11 PCF/BDF and Windows FON/FNT fonts metadata is parsed,
+  but their data is passed to `Database::with_face_data` as is.
 
 # Font vs Face
 
@@ -45,6 +52,11 @@ with a hot disk cache and ~860ms with a cold one.
 
 On Mac Mini M1 it takes just 9ms to load 898 fonts.
 
+With the `parallel` feature enabled, root directories will be walked and font files
+from each of them will be read and parsed using multiple threads,
+which mainly helps with a cold disk cache.
+Font faces are inserted in the same order as without the feature.
+
 # Safety
 
 The library relies on memory-mapped files, which is inherently unsafe.
@@ -63,16 +75,51 @@ then you can use the unsafe [`Database::make_shared_face_data`] function.
 
 extern crate alloc;
 
+#[cfg(feature = "android")]
+mod android;
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "fs")]
+mod cache;
+#[cfg(all(
+    unix,
+    feature = "fontconfig",
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+mod fontconfig;
+#[cfg(feature = "fs")]
+mod glob;
+mod report;
+#[cfg(feature = "fs")]
+mod scan;
+#[cfg(feature = "serde")]
+mod serialization;
 mod ttf_parser;
+#[cfg(feature = "watch")]
+mod watch;
 
 #[cfg(not(feature = "std"))]
 use alloc::{
     string::{String, ToString},
+    vec,
     vec::Vec,
 };
 
+#[cfg(all(
+    unix,
+    feature = "fontconfig",
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+pub use fontconfig::SystemFontConfig;
+#[cfg(feature = "fs")]
+pub use report::RescanReport;
+pub use report::{LoadFailure, LoadReport};
+#[cfg(feature = "fs")]
+pub use scan::{ScanOptions, SkipReason, SymlinkPolicy};
 pub use ttf_parser::Language;
 pub use ttf_parser::Width as Stretch;
+#[cfg(feature = "watch")]
+pub use watch::{FaceEvent, FontWatcher};
 
 use slotmap::SlotMap;
 use tinyvec::TinyVec;
@@ -105,7 +152,7 @@ impl ID {
     /// Should be used in tandem with [`Database::push_face_info`].
     #[inline]
     pub fn dummy() -> Self {
-        Self(InnerId::from(slotmap::KeyData::from_ffi(core::u64::MAX)))
+        Self(InnerId::from(slotmap::KeyData::from_ffi(u64::MAX)))
     }
 }
 
@@ -117,7 +164,8 @@ impl core::fmt::Display for ID {
 
 /// A list of possible font loading errors.
 #[derive(Debug)]
-enum LoadError {
+#[non_exhaustive]
+pub enum LoadError {
     /// A malformed font.
     ///
     /// Typically means that [ttf-parser](https://github.com/RazrFalcon/ttf-parser)
@@ -149,15 +197,46 @@ impl core::fmt::Display for LoadError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::IoError(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// A font database.
 #[derive(Clone, Debug)]
 pub struct Database {
     faces: SlotMap<InnerId, FaceInfo>,
-    family_serif: String,
-    family_sans_serif: String,
-    family_cursive: String,
-    family_fantasy: String,
-    family_monospace: String,
+    family_serif: Vec<String>,
+    family_sans_serif: Vec<String>,
+    family_cursive: Vec<String>,
+    family_fantasy: Vec<String>,
+    family_monospace: Vec<String>,
+    #[cfg(feature = "fs")]
+    scan_options: ScanOptions,
+    #[cfg(feature = "fs")]
+    scan_state: scan::ScanState,
+    #[cfg(feature = "fs")]
+    cache: Option<cache::FaceCache>,
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    match_rules: Option<std::sync::Arc<fontconfig::MatchRules>>,
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    system_font_config: Option<std::sync::Arc<SystemFontConfig>>,
+    /// Android fallback families as language tags and family names.
+    #[cfg(feature = "android")]
+    lang_families: Vec<(String, String)>,
 }
 
 impl Default for Database {
@@ -180,22 +259,46 @@ impl Database {
     pub fn new() -> Self {
         Database {
             faces: SlotMap::with_key(),
-            family_serif: "Times New Roman".to_string(),
-            family_sans_serif: "Arial".to_string(),
-            family_cursive: "Comic Sans MS".to_string(),
+            family_serif: vec!["Times New Roman".to_string()],
+            family_sans_serif: vec!["Arial".to_string()],
+            family_cursive: vec!["Comic Sans MS".to_string()],
             #[cfg(not(any(target_os = "macos", target_os = "ios")))]
-            family_fantasy: "Impact".to_string(),
+            family_fantasy: vec!["Impact".to_string()],
             #[cfg(any(target_os = "macos", target_os = "ios"))]
-            family_fantasy: "Papyrus".to_string(),
-            family_monospace: "Courier New".to_string(),
+            family_fantasy: vec!["Papyrus".to_string()],
+            family_monospace: vec!["Courier New".to_string()],
+            #[cfg(feature = "fs")]
+            scan_options: ScanOptions::new(),
+            #[cfg(feature = "fs")]
+            scan_state: scan::ScanState::default(),
+            #[cfg(feature = "fs")]
+            cache: None,
+            #[cfg(all(
+                unix,
+                feature = "fontconfig",
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            ))]
+            match_rules: None,
+            #[cfg(all(
+                unix,
+                feature = "fontconfig",
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            ))]
+            system_font_config: None,
+            #[cfg(feature = "android")]
+            lang_families: Vec::new(),
         }
     }
 
     /// Loads a font data into the `Database`.
     ///
     /// Will load all font faces in case of a font collection.
-    pub fn load_font_data(&mut self, data: Vec<u8>) {
-        self.load_font_source(Source::Binary(alloc::sync::Arc::new(data)));
+    ///
+    /// IDs of the loaded font faces are stored in [`LoadReport::faces`].
+    pub fn load_font_data(&mut self, data: Vec<u8>) -> LoadReport {
+        let mut report = LoadReport::default();
+        self.load_font_source_impl(Source::Binary(alloc::sync::Arc::new(data)), &mut report);
+        report
     }
 
     /// Loads a font from the given source into the `Database` and returns
@@ -203,194 +306,837 @@ impl Database {
     ///
     /// Will load all font faces in case of a font collection.
     pub fn load_font_source(&mut self, source: Source) -> TinyVec<[ID; 8]> {
-        let ids = source.with_data(|data| {
-            let n = ttf_parser::fonts_in_collection(data).unwrap_or(1);
-            let mut ids = TinyVec::with_capacity(n as usize);
-
-            for index in 0..n {
-                match parse_face_info(source.clone(), data, index) {
-                    Ok(mut info) => {
-                        let id = self.faces.insert_with_key(|k| {
-                            info.id = ID(k);
-                            info
-                        });
-                        ids.push(ID(id));
-                    }
-                    Err(e) => log::warn!(
-                        "Failed to load a font face {} from source cause {}.",
-                        index,
-                        e
-                    ),
+        let mut report = LoadReport::default();
+        self.load_font_source_impl(source, &mut report);
+        report.faces.into_iter().collect()
+    }
+
+    fn load_font_source_impl(&mut self, source: Source, report: &mut LoadReport) {
+        // Unpack binary containers only once.
+        let parsed = if let Source::Binary(ref data) = source {
+            let raw = data.as_ref().as_ref();
+            match unpack_font_data(raw) {
+                Some(Some(data)) => {
+                    let source = Source::Binary(alloc::sync::Arc::new(data));
+                    let faces = source.with_raw_data(|data| parse_faces(&source, raw, data));
+                    faces.map(|faces| (source, faces))
                 }
+                Some(None) => None,
+                None => Some((source.clone(), parse_faces(&source, raw, raw))),
             }
+        } else {
+            source
+                .with_raw_data(|raw| {
+                    with_unpacked_data(raw, |data| parse_faces(&source, raw, data))
+                })
+                .flatten()
+                .map(|faces| (source.clone(), faces))
+        };
 
-            ids
-        });
-
-        ids.unwrap_or_default()
-    }
+        let (source, faces) = match parsed {
+            Some(parsed) => parsed,
+            None => {
+                log::warn!(
+                    "Failed to load a font from source cause {}.",
+                    LoadError::MalformedFont
+                );
+                report
+                    .errors
+                    .push(LoadFailure::new(&source, None, LoadError::MalformedFont));
+                return;
+            }
+        };
 
-    /// Backend function used by load_font_file to load font files.
-    #[cfg(feature = "fs")]
-    fn load_fonts_from_file(&mut self, path: &std::path::Path, data: &[u8]) {
-        let source = Source::File(path.into());
-
-        let n = ttf_parser::fonts_in_collection(data).unwrap_or(1);
-        for index in 0..n {
-            match parse_face_info(source.clone(), data, index) {
-                Ok(info) => {
-                    self.push_face_info(info);
-                }
+        report.faces.reserve(faces.len());
+        for (index, face) in faces.into_iter().enumerate() {
+            match face {
+                Ok(info) => report.faces.push(self.push_face_info(info)),
                 Err(e) => {
                     log::warn!(
-                        "Failed to load a font face {} from '{}' cause {}.",
+                        "Failed to load a font face {} from source cause {}.",
                         index,
-                        path.display(),
                         e
-                    )
+                    );
+                    report
+                        .errors
+                        .push(LoadFailure::new(&source, Some(index as u32), e));
                 }
             }
         }
     }
 
+    /// Pushes font faces parsed from a font file into the `Database`.
+    #[cfg(feature = "fs")]
+    fn push_parsed_file(
+        &mut self,
+        path: &std::path::Path,
+        parsed: ParsedFile,
+        options: Option<&ScanOptions>,
+        report: &mut LoadReport,
+    ) {
+        let first_face = report.faces.len();
+
+        match parsed {
+            Some(faces) => {
+                for (index, face) in faces.into_iter().enumerate() {
+                    match face {
+                        Ok(info) => {
+                            if options.is_some_and(|o| o.rejects_face(&info)) {
+                                report
+                                    .skipped
+                                    .push((path.to_path_buf(), SkipReason::Rejected));
+                                continue;
+                            }
+
+                            report.faces.push(self.push_face_info(info));
+                        }
+                        Err(e) => {
+                            log::warn!(
+                                "Failed to load a font face {} from '{}' cause {}.",
+                                index,
+                                path.display(),
+                                e
+                            );
+                            report.errors.push(LoadFailure {
+                                path: Some(path.to_path_buf()),
+                                index: Some(index as u32),
+                                error: e,
+                            });
+                        }
+                    }
+                }
+            }
+            None => {
+                log::warn!(
+                    "Failed to load '{}' cause {}.",
+                    path.display(),
+                    LoadError::MalformedFont
+                );
+                report.errors.push(LoadFailure {
+                    path: Some(path.to_path_buf()),
+                    index: None,
+                    error: LoadError::MalformedFont,
+                });
+            }
+        }
+
+        let ids = report.faces[first_face..].iter().copied().collect();
+        report.files.push((path.to_path_buf(), ids));
+    }
+
     /// Loads a font file into the `Database`.
     ///
     /// Will load all font faces in case of a font collection.
-    #[cfg(all(feature = "fs", feature = "memmap"))]
+    ///
+    /// Returns IDs of the loaded font faces.
+    /// Malformed font faces are skipped and printed into the log.
+    #[cfg(feature = "fs")]
     pub fn load_font_file<P: AsRef<std::path::Path>>(
         &mut self,
         path: P,
-    ) -> Result<(), std::io::Error> {
-        self.load_font_file_impl(path.as_ref())
+    ) -> Result<TinyVec<[ID; 8]>, std::io::Error> {
+        let path = path.as_ref();
+        let mut report = LoadReport::default();
+        self.push_parsed_file(path, parse_font_file(path)?, None, &mut report);
+        Ok(report.faces.into_iter().collect())
     }
 
-    // A non-generic version.
-    #[cfg(all(feature = "fs", feature = "memmap"))]
-    fn load_font_file_impl(&mut self, path: &std::path::Path) -> Result<(), std::io::Error> {
-        let file = std::fs::File::open(path)?;
-        let data: &[u8] = unsafe { &memmap2::MmapOptions::new().map(&file)? };
+    /// Parses font files and pushes them into the `Database` in the same order.
+    ///
+    /// Files are parsed in parallel when the `parallel` feature is enabled.
+    #[cfg(feature = "fs")]
+    fn load_font_files(
+        &mut self,
+        files: Vec<std::path::PathBuf>,
+        options: &ScanOptions,
+        report: &mut LoadReport,
+    ) {
+        let stamps: Vec<_> = files
+            .iter()
+            .map(|path| self.file_stamp(path, options))
+            .collect();
 
-        self.load_fonts_from_file(path, data);
-        Ok(())
+        // Unchanged files with cached faces are not opened at all.
+        let cached: Vec<Option<ParsedFile>> = files
+            .iter()
+            .zip(&stamps)
+            .map(|(path, stamp)| {
+                let faces = self.cache.as_mut()?.get(path, (*stamp)?)?;
+                Some(Some(faces.into_iter().map(Ok).collect()))
+            })
+            .collect();
+
+        let parse = |(path, cached): (&std::path::PathBuf, &Option<ParsedFile>)| {
+            if cached.is_some() {
+                None
+            } else {
+                Some(parse_font_file(path))
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        let parsed: Vec<_> = {
+            use rayon::prelude::*;
+            files.par_iter().zip(cached.par_iter()).map(parse).collect()
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let parsed: Vec<_> = files.iter().zip(cached.iter()).map(parse).collect();
+
+        let iter = files.iter().zip(stamps).zip(cached).zip(parsed);
+        for (((path, stamp), cached), parsed) in iter {
+            let parsed = match parsed {
+                Some(parsed) => {
+                    self.cache_parsed_file(path, stamp, &parsed);
+                    parsed
+                }
+                None => Ok(cached.flatten()),
+            };
+
+            match parsed {
+                Ok(parsed) => {
+                    self.push_parsed_file(path, parsed, Some(options), report);
+                    // Remember loaded files, so they could be checked for changes by `rescan`.
+                    if let Some((_, ids)) = report.files.last() {
+                        let file = scan::ScannedFile {
+                            stamp,
+                            ids: ids.clone(),
+                        };
+                        self.scan_state.files.insert(path.clone(), file);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+                    report.errors.push(LoadFailure {
+                        path: Some(path.clone()),
+                        index: None,
+                        error: e.into(),
+                    });
+                }
+            }
+        }
     }
 
-    /// Loads a font file into the `Database`.
+    /// Returns a file stamp used to validate cached faces and to detect changes by `rescan`.
     ///
-    /// Will load all font faces in case of a font collection.
-    #[cfg(all(feature = "fs", not(feature = "memmap")))]
-    pub fn load_font_file<P: AsRef<std::path::Path>>(
+    /// Requires an additional `stat` call, therefore a stamp is returned only
+    /// when the cache or changes tracking is enabled.
+    #[cfg(feature = "fs")]
+    fn file_stamp(&self, path: &std::path::Path, options: &ScanOptions) -> Option<scan::FileStamp> {
+        if self.cache.is_none() && !options.tracks_changes() {
+            return None;
+        }
+
+        scan::FileStamp::new(path)
+    }
+
+    /// Stores faces parsed from a font file into the cache, when the cache is enabled.
+    ///
+    /// Files with malformed faces are not cached, so their errors would be reported each time.
+    #[cfg(feature = "fs")]
+    fn cache_parsed_file(
         &mut self,
-        path: P,
-    ) -> Result<(), std::io::Error> {
-        self.load_font_file_impl(path.as_ref())
+        path: &std::path::Path,
+        stamp: Option<scan::FileStamp>,
+        parsed: &Result<ParsedFile, std::io::Error>,
+    ) {
+        let (cache, stamp) = match (self.cache.as_mut(), stamp) {
+            (Some(cache), Some(stamp)) => (cache, stamp),
+            _ => return,
+        };
+
+        if let Ok(Some(faces)) = parsed {
+            // Cached faces are restored as `Source::File`, which is not suitable
+            // for files with data kept in memory, like gzip-compressed ones.
+            let faces: Option<Vec<FaceInfo>> = faces
+                .iter()
+                .map(|face| face.as_ref().ok().cloned())
+                .map(|face| face.filter(|face| matches!(face.source, Source::File(_))))
+                .collect();
+            if let Some(faces) = faces {
+                cache.insert(path, stamp, faces);
+            }
+        }
     }
 
-    // A non-generic version.
-    #[cfg(all(feature = "fs", not(feature = "memmap")))]
-    fn load_font_file_impl(&mut self, path: &std::path::Path) -> Result<(), std::io::Error> {
-        let data = std::fs::read(path)?;
+    /// Writes the cache to the disk, when it is enabled.
+    #[cfg(feature = "fs")]
+    fn save_cache(&mut self) {
+        if let Some(ref mut cache) = self.cache {
+            if let Err(e) = cache.save() {
+                log::warn!("Failed to save the font faces cache cause {}.", e);
+            }
+        }
+    }
 
-        self.load_fonts_from_file(path, &data);
-        Ok(())
+    /// Enables the persistent font faces cache in the default location.
+    ///
+    /// Which is `$XDG_CACHE_HOME/fontdb` (or `~/.cache/fontdb`) on Unix,
+    /// `~/Library/Caches/fontdb` on macOS/iOS and `%LOCALAPPDATA%\fontdb` on Windows.
+    ///
+    /// Returns `false` when the default location cannot be determined.
+    /// See [`Database::set_cache_dir`] for details.
+    #[cfg(feature = "fs")]
+    pub fn enable_cache(&mut self) -> bool {
+        match cache::FaceCache::default_dir() {
+            Some(dir) => {
+                self.set_cache_dir(dir);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enables the persistent font faces cache stored in the selected directory.
+    ///
+    /// Font faces of files loaded via `load_fonts_dir`, `load_fonts_dir_with`,
+    /// `load_system_fonts` and `rescan` are stored in the cache keyed by
+    /// a file path, size and modification time.
+    /// Unchanged files are not opened on subsequent loads.
+    /// Files containing malformed faces are never cached.
+    /// Neither are gzip-compressed files, which have to be decompressed anyway.
+    ///
+    /// The cache is written at the end of each of those calls.
+    /// Each `fontdb` version uses a separate cache file in the same directory.
+    #[cfg(feature = "fs")]
+    pub fn set_cache_dir<P: AsRef<std::path::Path>>(&mut self, dir: P) {
+        self.cache = Some(cache::FaceCache::open(dir.as_ref()));
+    }
+
+    /// Disables the persistent font faces cache.
+    ///
+    /// Cache files are left on the disk.
+    #[cfg(feature = "fs")]
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
     }
 
     /// Loads font files from the selected directory into the `Database`.
     ///
     /// This method will scan directories recursively.
     ///
-    /// Will load `ttf`, `otf`, `ttc` and `otc` fonts.
+    /// Which files will be loaded is controlled by [`ScanOptions`].
+    /// By default, will load `ttf`, `otf`, `ttc` and `otc` fonts.
+    /// And `woff`/`woff2` fonts when the `woff`/`woff2` features are enabled.
     ///
     /// Unlike other `load_*` methods, this one doesn't return an error.
     /// It will simply skip malformed fonts and will print a warning into the log for each of them.
+    /// All failures and skipped files are collected into the returned [`LoadReport`] as well.
+    /// Loaded font faces IDs are available per file via [`LoadReport::files`].
     #[cfg(feature = "fs")]
-    pub fn load_fonts_dir<P: AsRef<std::path::Path>>(&mut self, dir: P) {
-        self.load_fonts_dir_impl(dir.as_ref(), &mut Default::default())
+    pub fn load_fonts_dir<P: AsRef<std::path::Path>>(&mut self, dir: P) -> LoadReport {
+        let mut report = LoadReport::default();
+        self.load_fonts_roots(&[dir.as_ref().to_path_buf()], &mut report);
+        self.save_cache();
+        report
     }
 
+    /// Loads font files from the selected directory into the `Database`
+    /// using the provided scanning options.
+    ///
+    /// Same as [`Database::load_fonts_dir`], but ignores options set
+    /// via [`Database::set_scan_options`].
     #[cfg(feature = "fs")]
-    fn canonicalize(
+    pub fn load_fonts_dir_with<P: AsRef<std::path::Path>>(
+        &mut self,
+        dir: P,
+        options: &ScanOptions,
+    ) -> LoadReport {
+        let mut report = LoadReport::default();
+        self.load_fonts_roots_with(&[dir.as_ref().to_path_buf()], options, &mut report);
+        self.save_cache();
+        report
+    }
+
+    /// Loads font files from a zip or tar archive into the `Database`.
+    ///
+    /// Fonts are recognized by their content, so entry names are not checked.
+    /// Supports stored and deflated zip entries.
+    ///
+    /// Loaded font faces use [`Source::Archive`], so their data will be extracted
+    /// from the archive again on access. The archive is memory-mapped
+    /// when the `memmap` feature is enabled.
+    ///
+    /// Archive members are reported as the archive path joined with the member path.
+    #[cfg(feature = "archive")]
+    pub fn load_fonts_archive<P: AsRef<std::path::Path>>(&mut self, path: P) -> LoadReport {
+        let path = path.as_ref();
+        let mut report = LoadReport::default();
+
+        let data = match read_archive(path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+                report.errors.push(LoadFailure {
+                    path: Some(path.to_path_buf()),
+                    index: None,
+                    error: e.into(),
+                });
+                return report;
+            }
+        };
+
+        let entries = match archive::entries(&data) {
+            Some(entries) => entries,
+            None => {
+                log::warn!(
+                    "Failed to load '{}' cause {}.",
+                    path.display(),
+                    LoadError::MalformedFont
+                );
+                report.errors.push(LoadFailure {
+                    path: Some(path.to_path_buf()),
+                    index: None,
+                    error: LoadError::MalformedFont,
+                });
+                return report;
+            }
+        };
+
+        for entry in entries {
+            let member_path = path.join(&entry.name);
+            let entry_data = match entry.data(&data) {
+                Some(entry_data) => entry_data,
+                None => {
+                    log::warn!(
+                        "Failed to load '{}' cause {}.",
+                        member_path.display(),
+                        LoadError::MalformedFont
+                    );
+                    report.errors.push(LoadFailure {
+                        path: Some(member_path),
+                        index: None,
+                        error: LoadError::MalformedFont,
+                    });
+                    continue;
+                }
+            };
+
+            if !is_font_signature(&entry_data) {
+                report.skipped.push((member_path, SkipReason::NotAFont));
+                continue;
+            }
+
+            let offset = entry.offset() as u64;
+            let source = Source::Archive(path.to_path_buf(), entry.name, offset);
+            let parsed =
+                with_unpacked_data(&entry_data, |data| parse_faces(&source, &entry_data, data));
+            self.push_parsed_file(&member_path, parsed, None, &mut report);
+        }
+
+        report
+    }
+
+    /// Sets options that will be used by `load_fonts_dir` and `load_system_fonts`.
+    #[cfg(feature = "fs")]
+    pub fn set_scan_options(&mut self, options: ScanOptions) {
+        self.scan_options = options;
+    }
+
+    /// Returns options used by `load_fonts_dir` and `load_system_fonts`.
+    #[cfg(feature = "fs")]
+    pub fn scan_options(&self) -> &ScanOptions {
+        &self.scan_options
+    }
+
+    /// Returns the fontconfig configuration used by the last `load_system_fonts` call.
+    ///
+    /// `None` when system fonts were not loaded yet.
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    pub fn system_font_config(&self) -> Option<&SystemFontConfig> {
+        self.system_font_config.as_deref()
+    }
+
+    /// Checks that a walked entry was not visited yet and marks it as visited.
+    #[cfg(feature = "fs")]
+    fn visit_entry(
         &self,
-        path: std::path::PathBuf,
-        entry: std::fs::DirEntry,
+        entry: &scan::WalkEntry,
         seen: &mut std::collections::HashSet<std::path::PathBuf>,
-    ) -> Option<(std::path::PathBuf, std::fs::FileType)> {
-        let file_type = entry.file_type().ok()?;
-        if !file_type.is_symlink() {
+        pending: &[std::path::PathBuf],
+    ) -> bool {
+        if !entry.file_type.is_symlink() {
             if !seen.is_empty() {
-                if seen.contains(&path) {
-                    return None;
+                if seen.contains(&entry.path) {
+                    return false;
                 }
-                seen.insert(path.clone());
+                seen.insert(entry.path.clone());
             }
 
-            return Some((path, file_type));
+            return true;
         }
 
-        if seen.is_empty() && file_type.is_dir() {
+        if seen.is_empty() && entry.file_type.is_dir() {
             seen.reserve(8192 / std::mem::size_of::<std::path::PathBuf>());
 
             for (_, info) in self.faces.iter() {
                 let path = match &info.source {
-                    Source::Binary(_) => continue,
+                    Source::Binary(_) | Source::Custom(_) => continue,
                     Source::File(path) => path.to_path_buf(),
-                    #[cfg(feature = "memmap")]
                     Source::SharedFile(path, _) => path.to_path_buf(),
+                    #[cfg(feature = "archive")]
+                    Source::Archive(..) => continue,
                 };
                 seen.insert(path);
             }
+
+            // Files that were found, but not loaded yet.
+            seen.extend(pending.iter().cloned());
         }
 
-        let stat = std::fs::metadata(&path).ok()?;
-        if stat.is_symlink() {
-            return None;
+        let target = match entry.target {
+            Some(ref target) => target,
+            None => return false,
+        };
+
+        if seen.contains(target) {
+            return false;
         }
+        seen.insert(target.clone());
+        true
+    }
 
-        let canon = std::fs::canonicalize(path).ok()?;
-        if seen.contains(&canon) {
-            return None;
+    // Loads root directories using the database scanning options.
+    #[cfg(feature = "fs")]
+    fn load_fonts_roots(&mut self, dirs: &[std::path::PathBuf], report: &mut LoadReport) {
+        let options = self.scan_options.clone();
+        self.load_fonts_roots_with(dirs, &options, report);
+    }
+
+    // Loads root directories and measures the time spent on each of them.
+    //
+    // Roots are walked concurrently when the `parallel` feature is enabled,
+    // but files are still deduplicated and loaded in the roots order.
+    #[cfg(feature = "fs")]
+    fn load_fonts_roots_with(
+        &mut self,
+        dirs: &[std::path::PathBuf],
+        options: &ScanOptions,
+        report: &mut LoadReport,
+    ) {
+        let roots: Vec<_> = dirs.iter().map(|dir| (dir.as_path(), options)).collect();
+        let walked = scan::walk_roots(&roots);
+
+        let mut seen = Default::default();
+        for (dir, walked) in dirs.iter().zip(walked) {
+            let now = std::time::Instant::now();
+            let mut files = Vec::new();
+            self.collect_font_files(dir, walked.entries, &mut seen, &mut files, report);
+
+            self.load_font_files(files, options, report);
+            self.scan_state.add_root(dir, options);
+
+            report
+                .roots
+                .push((dir.clone(), walked.elapsed + now.elapsed()));
         }
-        seen.insert(canon.clone());
-        Some((canon, stat.file_type()))
     }
 
-    // A non-generic version.
+    /// Rescans directories previously loaded via `load_fonts_dir`, `load_fonts_dir_with`
+    /// and `load_system_fonts`.
+    ///
+    /// Font faces from deleted files are removed from the `Database`,
+    /// new files are loaded and files which size or modification time
+    /// have changed are parsed again.
+    ///
+    /// Faces from changed files that are still present keep their IDs.
+    /// Files that were loaded via other methods are not affected.
+    ///
+    /// Unchanged files are skipped only when directories were loaded with
+    /// [`ScanOptions::track_changes`] or with the cache enabled.
+    /// Otherwise, all previously loaded files are parsed again.
+    #[cfg(feature = "fs")]
+    pub fn rescan(&mut self) -> RescanReport {
+        let mut result = RescanReport::default();
+
+        // Deduplicate only against fonts that were not loaded via scanning.
+        // Root directories are added to skip symlinks to them and to keep the set non-empty,
+        // otherwise `canonicalize` would populate it with all loaded fonts.
+        let mut seen: std::collections::HashSet<_> = self
+            .faces
+            .iter()
+            .filter_map(|(_, info)| info.source.path())
+            .filter(|path| !self.scan_state.files.contains_key(*path))
+            .map(|path| path.to_path_buf())
+            .collect();
+        for (dir, _) in &self.scan_state.roots {
+            seen.insert(std::fs::canonicalize(dir).unwrap_or_else(|_| dir.clone()));
+        }
+
+        let mut found = std::collections::HashSet::new();
+        let roots = self.scan_state.roots.clone();
+        let walk: Vec<_> = roots
+            .iter()
+            .map(|(dir, options)| (dir.as_path(), options))
+            .collect();
+        let walked = scan::walk_roots(&walk);
+        for ((dir, options), walked) in roots.iter().zip(walked) {
+            let now = std::time::Instant::now();
+            let mut files = Vec::new();
+            self.collect_font_files(dir, walked.entries, &mut seen, &mut files, &mut result.load);
+
+            let mut new_files = Vec::new();
+            for path in files {
+                if !found.insert(path.clone()) {
+                    continue;
+                }
+
+                match self.scan_state.files.get(&path) {
+                    Some(file)
+                        if file.stamp.is_some() && file.stamp == scan::FileStamp::new(&path) => {}
+                    Some(_) => self.reload_scanned_file(&path, options, &mut result),
+                    None => new_files.push(path),
+                }
+            }
+
+            let first_face = result.load.faces.len();
+            self.load_font_files(new_files, options, &mut result.load);
+            result
+                .added
+                .extend_from_slice(&result.load.faces[first_face..]);
+
+            result
+                .load
+                .roots
+                .push((dir.clone(), walked.elapsed + now.elapsed()));
+        }
+
+        let deleted: Vec<_> = self
+            .scan_state
+            .files
+            .keys()
+            .filter(|path| !found.contains(*path))
+            .cloned()
+            .collect();
+        for path in deleted {
+            if let Some(file) = self.scan_state.files.remove(&path) {
+                for id in file.ids {
+                    if self.faces.remove(id.0).is_some() {
+                        result.removed.push(id);
+                    }
+                }
+            }
+        }
+
+        self.save_cache();
+        result
+    }
+
+    /// Starts watching directories previously loaded via `load_fonts_dir`,
+    /// `load_fonts_dir_with` and `load_system_fonts`.
+    ///
+    /// `on_change` is called from a background thread each time a watched directory changes.
+    /// It should not access the `Database`, but rather schedule
+    /// a [`Database::apply_watched_changes`] call.
+    ///
+    /// Directories loaded after this call are not watched.
+    /// Directories should be loaded with [`ScanOptions::track_changes`] enabled,
+    /// otherwise each change will parse all of their files again.
+    #[cfg(feature = "watch")]
+    pub fn watch<F: FnMut() + Send + 'static>(
+        &self,
+        on_change: F,
+    ) -> Result<FontWatcher, std::io::Error> {
+        let roots = self.scan_state.roots.iter().map(|(dir, _)| dir.as_path());
+        FontWatcher::new(roots, on_change)
+    }
+
+    /// Applies changes detected by a [`FontWatcher`].
+    ///
+    /// Rescans all loaded directories via [`Database::rescan`] when anything has changed
+    /// and returns the resulting font face changes.
+    /// Loading errors are printed into the log.
+    #[cfg(feature = "watch")]
+    pub fn apply_watched_changes(&mut self, watcher: &FontWatcher) -> Vec<FaceEvent> {
+        if !watcher.take_changes() {
+            return Vec::new();
+        }
+
+        let report = self.rescan();
+        let removed = report.removed.into_iter().map(FaceEvent::Removed);
+        let added = report.added.into_iter().map(FaceEvent::Added);
+        let changed = report.changed.into_iter().map(FaceEvent::Changed);
+        removed.chain(added).chain(changed).collect()
+    }
+
+    // Parses a changed file again, preserving IDs of the faces that are still present.
     #[cfg(feature = "fs")]
-    fn load_fonts_dir_impl(
+    fn reload_scanned_file(
         &mut self,
-        dir: &std::path::Path,
-        seen: &mut std::collections::HashSet<std::path::PathBuf>,
+        path: &std::path::Path,
+        options: &ScanOptions,
+        result: &mut RescanReport,
     ) {
-        let fonts_dir = match std::fs::read_dir(dir) {
-            Ok(dir) => dir,
-            Err(_) => return,
+        let old_ids = self
+            .scan_state
+            .files
+            .remove(path)
+            .map(|file| file.ids)
+            .unwrap_or_default();
+
+        // Previously loaded faces and their indices in the file.
+        let mut old_faces: Vec<(u32, ID)> = old_ids
+            .iter()
+            .filter_map(|id| self.faces.get(id.0).map(|info| (info.index, *id)))
+            .collect();
+
+        let mut ids = TinyVec::new();
+        // Unreadable files are not remembered, so they will be checked again on the next rescan.
+        let mut readable = true;
+        let stamp = self.file_stamp(path, options);
+        let parsed = parse_font_file(path);
+        self.cache_parsed_file(path, stamp, &parsed);
+        let faces = match parsed {
+            Ok(Some(faces)) => faces,
+            Ok(None) => {
+                log::warn!(
+                    "Failed to load '{}' cause {}.",
+                    path.display(),
+                    LoadError::MalformedFont
+                );
+                result.load.errors.push(LoadFailure {
+                    path: Some(path.to_path_buf()),
+                    index: None,
+                    error: LoadError::MalformedFont,
+                });
+                Vec::new()
+            }
+            Err(e) => {
+                log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+                result.load.errors.push(LoadFailure {
+                    path: Some(path.to_path_buf()),
+                    index: None,
+                    error: e.into(),
+                });
+                readable = false;
+                Vec::new()
+            }
         };
 
-        for entry in fonts_dir.flatten() {
-            let (path, file_type) = match self.canonicalize(entry.path(), entry, seen) {
-                Some(v) => v,
-                None => continue,
+        for (index, face) in faces.into_iter().enumerate() {
+            let mut info = match face {
+                Ok(info) => info,
+                Err(e) => {
+                    log::warn!(
+                        "Failed to load a font face {} from '{}' cause {}.",
+                        index,
+                        path.display(),
+                        e
+                    );
+                    result.load.errors.push(LoadFailure {
+                        path: Some(path.to_path_buf()),
+                        index: Some(index as u32),
+                        error: e,
+                    });
+                    continue;
+                }
             };
 
-            if file_type.is_file() {
-                match path.extension().and_then(|e| e.to_str()) {
-                    #[rustfmt::skip] // keep extensions match as is
-                    Some("ttf") | Some("ttc") | Some("TTF") | Some("TTC") |
-                    Some("otf") | Some("otc") | Some("OTF") | Some("OTC") => {
-                        if let Err(e) = self.load_font_file(&path) {
-                            log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+            if options.rejects_face(&info) {
+                result
+                    .load
+                    .skipped
+                    .push((path.to_path_buf(), SkipReason::Rejected));
+                continue;
+            }
+
+            let old_id = old_faces
+                .iter()
+                .position(|(index, _)| *index == info.index)
+                .map(|pos| old_faces.swap_remove(pos).1);
+
+            let id = match old_id {
+                Some(id) => {
+                    let face = &mut self.faces[id.0];
+                    if is_same_face(face, &info) {
+                        // Data kept in memory has to be updated even when faces are the same.
+                        if let Source::SharedFile(..) = info.source {
+                            face.source = info.source;
                         }
-                    },
-                    _ => {}
+
+                        ids.push(id);
+                        continue;
+                    }
+
+                    info.id = id;
+                    *face = info;
+                    result.changed.push(id);
+                    id
+                }
+                None => {
+                    let id = self.push_face_info(info);
+                    result.added.push(id);
+                    id
                 }
-            } else if file_type.is_dir() {
-                self.load_fonts_dir_impl(&path, seen);
+            };
+
+            ids.push(id);
+            result.load.faces.push(id);
+        }
+
+        for (_, id) in old_faces {
+            self.faces.remove(id.0);
+            result.removed.push(id);
+        }
+
+        if readable {
+            result.load.files.push((path.to_path_buf(), ids.clone()));
+            let file = scan::ScannedFile { stamp, ids };
+            self.scan_state.files.insert(path.to_path_buf(), file);
+        }
+    }
+
+    // Collects font files from walked directory entries recursively,
+    // skipping files and directories that were already visited.
+    #[cfg(feature = "fs")]
+    fn collect_font_files(
+        &self,
+        dir: &std::path::Path,
+        entries: Result<Vec<scan::WalkEntry>, std::io::Error>,
+        seen: &mut std::collections::HashSet<std::path::PathBuf>,
+        files: &mut Vec<std::path::PathBuf>,
+        report: &mut LoadReport,
+    ) {
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                report.unreadable_dirs.push((dir.to_path_buf(), e));
+                return;
+            }
+        };
+
+        for entry in entries {
+            if let scan::WalkKind::Excluded = entry.kind {
+                if entry.file_type.is_file() {
+                    report.skipped.push((entry.path, SkipReason::Excluded));
+                }
+
+                continue;
+            }
+
+            if !self.visit_entry(&entry, seen, files) {
+                continue;
+            }
+
+            let path = entry.target.unwrap_or(entry.path);
+            match entry.kind {
+                scan::WalkKind::File(Ok(())) => files.push(path),
+                scan::WalkKind::File(Err(reason)) => report.skipped.push((path, reason)),
+                scan::WalkKind::Dir(entries) => {
+                    self.collect_font_files(&path, entries, seen, files, report);
+                }
+                scan::WalkKind::Excluded | scan::WalkKind::Other => {}
             }
         }
     }
 
     /// Attempts to load system fonts.
     ///
-    /// Supports Windows, Linux and macOS.
+    /// Supports Windows, Linux, macOS, iOS and Android.
     ///
     /// System fonts loading is a surprisingly complicated task,
     /// mostly unsolvable without interacting with system libraries.
@@ -398,36 +1144,35 @@ impl Database {
     /// will simply scan some predefined directories.
     /// Which means that fonts that are not in those directories must
     /// be added manually.
+    ///
+    /// All failures and skipped files are collected into the returned [`LoadReport`].
     #[cfg(feature = "fs")]
-    pub fn load_system_fonts(&mut self) {
+    pub fn load_system_fonts(&mut self) -> LoadReport {
+        let mut report = LoadReport::default();
+
         #[cfg(target_os = "windows")]
         {
-            let mut seen = Default::default();
+            let mut dirs = Vec::new();
             if let Some(ref system_root) = std::env::var_os("SYSTEMROOT") {
                 let system_root_path = std::path::Path::new(system_root);
-                self.load_fonts_dir_impl(&system_root_path.join("Fonts"), &mut seen);
+                dirs.push(system_root_path.join("Fonts"));
             } else {
-                self.load_fonts_dir_impl("C:\\Windows\\Fonts\\".as_ref(), &mut seen);
+                dirs.push("C:\\Windows\\Fonts\\".into());
             }
 
             if let Ok(ref home) = std::env::var("USERPROFILE") {
                 let home_path = std::path::Path::new(home);
-                self.load_fonts_dir_impl(
-                    &home_path.join("AppData\\Local\\Microsoft\\Windows\\Fonts"),
-                    &mut seen,
-                );
-                self.load_fonts_dir_impl(
-                    &home_path.join("AppData\\Roaming\\Microsoft\\Windows\\Fonts"),
-                    &mut seen,
-                );
+                dirs.push(home_path.join("AppData\\Local\\Microsoft\\Windows\\Fonts"));
+                dirs.push(home_path.join("AppData\\Roaming\\Microsoft\\Windows\\Fonts"));
             }
+
+            self.load_fonts_roots(&dirs, &mut report);
         }
 
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         {
-            let mut seen = Default::default();
-            self.load_fonts_dir_impl("/Library/Fonts".as_ref(), &mut seen);
-            self.load_fonts_dir_impl("/System/Library/Fonts".as_ref(), &mut seen);
+            let mut dirs: Vec<std::path::PathBuf> =
+                vec!["/Library/Fonts".into(), "/System/Library/Fonts".into()];
             // Downloadable fonts, location varies on major macOS releases
             if let Ok(dir) = std::fs::read_dir("/System/Library/AssetsV2") {
                 for entry in dir {
@@ -440,43 +1185,269 @@ impl Database {
                         .to_string_lossy()
                         .starts_with("com_apple_MobileAsset_Font")
                     {
-                        self.load_fonts_dir_impl(&entry.path(), &mut seen);
+                        dirs.push(entry.path());
                     }
                 }
             }
-            self.load_fonts_dir_impl("/Network/Library/Fonts".as_ref(), &mut seen);
+            dirs.push("/Network/Library/Fonts".into());
 
             if let Ok(ref home) = std::env::var("HOME") {
                 let home_path = std::path::Path::new(home);
-                self.load_fonts_dir_impl(&home_path.join("Library/Fonts"), &mut seen);
+                dirs.push(home_path.join("Library/Fonts"));
             }
+
+            self.load_fonts_roots(&dirs, &mut report);
         }
 
         // Redox OS.
         #[cfg(target_os = "redox")]
         {
-            let mut seen = Default::default();
-            self.load_fonts_dir_impl("/ui/fonts".as_ref(), &mut seen);
+            self.load_fonts_roots(&["/ui/fonts".into()], &mut report);
         }
 
         // Linux.
-        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
         {
             #[cfg(feature = "fontconfig")]
             {
-                if !self.load_fontconfig() {
+                if !self.load_fontconfig(fontconfig::Config::load(), &mut report) {
                     log::warn!("Fallback to loading from known font dir paths.");
-                    self.load_no_fontconfig();
+                    self.load_no_fontconfig(&mut report);
                 }
             }
 
             #[cfg(not(feature = "fontconfig"))]
             {
-                self.load_no_fontconfig();
+                self.load_no_fontconfig(&mut report);
             }
         }
+
+        // Android.
+        #[cfg(target_os = "android")]
+        {
+            #[cfg(feature = "android")]
+            {
+                if !self.load_android_system_fonts(&mut report) {
+                    log::warn!("Fallback to loading fonts from /system/fonts.");
+                    self.load_fonts_roots(&["/system/fonts".into()], &mut report);
+                }
+            }
+
+            #[cfg(not(feature = "android"))]
+            {
+                self.load_fonts_roots(&["/system/fonts".into()], &mut report);
+            }
+        }
+
+        self.save_cache();
+        report
     }
 
+    // Android.
+    #[cfg(all(target_os = "android", feature = "android"))]
+    fn load_android_system_fonts(&mut self, report: &mut LoadReport) -> bool {
+        use std::path::Path;
+
+        let fonts_dir = Path::new("/system/fonts");
+
+        // Android 15+ and Android 5.0+ configs.
+        for config in ["/system/etc/font_fallback.xml", "/system/etc/fonts.xml"] {
+            if self
+                .load_android_fonts_xml_impl(Path::new(config), fonts_dir, report)
+                .is_ok()
+            {
+                return true;
+            }
+        }
+
+        // Pre Android 5.0 configs.
+        let system = Path::new("/system/etc/system_fonts.xml");
+        if self
+            .load_android_fonts_xml_impl(system, fonts_dir, report)
+            .is_ok()
+        {
+            let fallback = Path::new("/system/etc/fallback_fonts.xml");
+            let _ = self.load_android_fonts_xml_impl(fallback, fonts_dir, report);
+            return true;
+        }
+
+        false
+    }
+
+    /// Loads fonts listed in an Android `fonts.xml` file.
+    ///
+    /// Font paths are resolved relative to `fonts_dir`, which is `/system/fonts` on Android.
+    ///
+    /// Each declared font will be loaded as a separate face, using the declared
+    /// face index, weight and style. Fallback families, like the ones with a `lang`
+    /// attribute, will be loaded as well.
+    ///
+    /// Families named `sans-serif`, `serif`, `monospace` and `cursive`
+    /// and aliases to them will be used to set generic font families.
+    /// Fallback families with a `lang` attribute will be preferred for generic
+    /// families by queries with a matching [`Query::lang`].
+    ///
+    /// Used by [`Database::load_system_fonts`] on Android, but works on any platform.
+    ///
+    /// Returns an error only when the config itself cannot be read or parsed.
+    #[cfg(feature = "android")]
+    pub fn load_android_fonts_xml<P: AsRef<std::path::Path>, D: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+        fonts_dir: D,
+    ) -> Result<LoadReport, std::io::Error> {
+        let mut report = LoadReport::default();
+        self.load_android_fonts_xml_impl(path.as_ref(), fonts_dir.as_ref(), &mut report)?;
+        Ok(report)
+    }
+
+    // A non-generic version.
+    #[cfg(feature = "android")]
+    fn load_android_fonts_xml_impl(
+        &mut self,
+        path: &std::path::Path,
+        fonts_dir: &std::path::Path,
+        report: &mut LoadReport,
+    ) -> Result<(), std::io::Error> {
+        let now = std::time::Instant::now();
+        let text = std::fs::read_to_string(path)?;
+        let config = android::parse(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        // Fonts are usually listed multiple times, so parse each file only once.
+        let mut files: std::collections::HashMap<_, Option<(usize, Vec<_>)>> = Default::default();
+        let mut family_names = std::collections::HashMap::new();
+
+        for family in &config.families {
+            let mut family_name = None;
+            for font in &family.fonts {
+                let path = fonts_dir.join(&font.path);
+                let file =
+                    files
+                        .entry(path.clone())
+                        .or_insert_with(|| match parse_font_file(&path) {
+                            Ok(Some(faces)) => {
+                                report.files.push((path.clone(), TinyVec::new()));
+                                let faces = faces.into_iter().map(Some).collect();
+                                Some((report.files.len() - 1, faces))
+                            }
+                            Ok(None) => {
+                                log::warn!(
+                                    "Failed to load '{}' cause {}.",
+                                    path.display(),
+                                    LoadError::MalformedFont
+                                );
+                                report.errors.push(LoadFailure {
+                                    path: Some(path.clone()),
+                                    index: None,
+                                    error: LoadError::MalformedFont,
+                                });
+                                None
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to load '{}' cause {}.", path.display(), e);
+                                report.errors.push(LoadFailure {
+                                    path: Some(path.clone()),
+                                    index: None,
+                                    error: e.into(),
+                                });
+                                None
+                            }
+                        });
+
+                let (file_idx, faces) = match file {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let mut info = match faces.get_mut(font.index as usize) {
+                    Some(Some(Ok(info))) => info.clone(),
+                    Some(face @ Some(Err(_))) => {
+                        if let Some(Err(e)) = face.take() {
+                            log::warn!(
+                                "Failed to load a font face {} from '{}' cause {}.",
+                                font.index,
+                                path.display(),
+                                e
+                            );
+                            report.errors.push(LoadFailure {
+                                path: Some(path),
+                                index: Some(font.index),
+                                error: e,
+                            });
+                        }
+                        continue;
+                    }
+                    // Already reported.
+                    Some(None) => continue,
+                    None => {
+                        log::warn!(
+                            "Failed to load a font face {} from '{}' cause {}.",
+                            font.index,
+                            path.display(),
+                            LoadError::MalformedFont
+                        );
+                        report.errors.push(LoadFailure {
+                            path: Some(path),
+                            index: Some(font.index),
+                            error: LoadError::MalformedFont,
+                        });
+                        continue;
+                    }
+                };
+
+                if let Some(weight) = font.weight {
+                    info.weight = Weight(weight);
+                }
+                if let Some(style) = font.style {
+                    info.style = style;
+                }
+                if family_name.is_none() {
+                    family_name = info.families.first().map(|(name, _)| name.clone());
+                }
+
+                let id = self.push_face_info(info);
+                report.faces.push(id);
+                report.files[*file_idx].1.push(id);
+            }
+
+            if let (Some(lang), Some(family_name)) = (&family.lang, &family_name) {
+                let entry = (lang.clone(), family_name.clone());
+                if !self.lang_families.contains(&entry) {
+                    self.lang_families.push(entry);
+                }
+            }
+
+            if let (Some(name), Some(family_name)) = (&family.name, family_name) {
+                family_names.entry(name.as_str()).or_insert(family_name);
+            }
+        }
+
+        for generic in ["sans-serif", "serif", "monospace", "cursive"] {
+            let name = family_names.get(generic).or_else(|| {
+                config
+                    .aliases
+                    .iter()
+                    .find(|alias| alias.name == generic && alias.weight.is_none())
+                    .and_then(|alias| family_names.get(alias.to.as_str()))
+            });
+
+            if let Some(name) = name {
+                match generic {
+                    "sans-serif" => self.set_sans_serif_family(name),
+                    "serif" => self.set_serif_family(name),
+                    "monospace" => self.set_monospace_family(name),
+                    _ => self.set_cursive_family(name),
+                }
+            }
+        }
+
+        report.roots.push((fonts_dir.to_path_buf(), now.elapsed()));
+        Ok(())
+    }
 
     // Linux.
     #[cfg(all(
@@ -484,99 +1455,87 @@ impl Database {
         feature = "fs",
         not(any(target_os = "macos", target_os = "ios", target_os = "android"))
     ))]
-    fn load_no_fontconfig(&mut self) {
-        let mut seen = Default::default();
-        self.load_fonts_dir_impl("/usr/share/fonts/".as_ref(), &mut seen);
-        self.load_fonts_dir_impl("/usr/local/share/fonts/".as_ref(), &mut seen);
+    fn load_no_fontconfig(&mut self, report: &mut LoadReport) {
+        let mut dirs: Vec<std::path::PathBuf> =
+            vec!["/usr/share/fonts/".into(), "/usr/local/share/fonts/".into()];
 
         if let Ok(ref home) = std::env::var("HOME") {
             let home_path = std::path::Path::new(home);
-            self.load_fonts_dir_impl(&home_path.join(".fonts"), &mut seen);
-            self.load_fonts_dir_impl(&home_path.join(".local/share/fonts"), &mut seen);
+            dirs.push(home_path.join(".fonts"));
+            dirs.push(home_path.join(".local/share/fonts"));
         }
+
+        self.load_fonts_roots(&dirs, report);
     }
 
-    // Linux.
+    /// Loads fonts using fontconfig, like [`Database::load_system_fonts`] does on Linux,
+    /// but reads fontconfig environment variables, like `FONTCONFIG_FILE`, from `vars`
+    /// instead of the process environment.
+    ///
+    /// Not a part of the public API. Allows testing configurations without modifying
+    /// the process-wide environment.
+    #[doc(hidden)]
     #[cfg(all(
         unix,
         feature = "fontconfig",
         not(any(target_os = "macos", target_os = "ios", target_os = "android"))
     ))]
-    fn load_fontconfig(&mut self) -> bool {
-        use std::path::Path;
-
-        let mut fontconfig = fontconfig_parser::FontConfig::default();
-        let home = std::env::var("HOME");
-
-        if let Ok(ref config_file) = std::env::var("FONTCONFIG_FILE") {
-            let _ = fontconfig.merge_config(Path::new(config_file));
-        } else {
-            let xdg_config_home = if let Ok(val) = std::env::var("XDG_CONFIG_HOME") {
-                Some(val.into())
-            } else if let Ok(ref home) = home {
-                // according to https://specifications.freedesktop.org/basedir-spec/basedir-spec-latest.html
-                // $XDG_CONFIG_HOME should default to $HOME/.config if not set
-                Some(Path::new(home).join(".config"))
-            } else {
-                None
-            };
+    pub fn load_fontconfig_with_vars(&mut self, vars: &[(&str, &str)]) -> LoadReport {
+        let mut report = LoadReport::default();
+        let config = fontconfig::Config::load_with(&|name| {
+            vars.iter()
+                .find(|(var, value)| *var == name && !value.is_empty())
+                .map(|(_, value)| value.into())
+        });
+        self.load_fontconfig(config, &mut report);
+        report
+    }
 
-            let read_global = match xdg_config_home {
-                Some(p) => fontconfig
-                    .merge_config(&p.join("fontconfig/fonts.conf"))
-                    .is_err(),
-                None => true,
-            };
+    // Linux.
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    fn load_fontconfig(
+        &mut self,
+        mut fontconfig: fontconfig::Config,
+        report: &mut LoadReport,
+    ) -> bool {
+        self.system_font_config = Some(std::sync::Arc::new(fontconfig.take_system_config()));
+
+        for generic in ["serif", "sans-serif", "monospace", "cursive", "fantasy"] {
+            let families = fontconfig::alias_families(&fontconfig.aliases, generic);
+            if families.is_empty() {
+                continue;
+            }
 
-            if read_global {
-                let _ = fontconfig.merge_config(Path::new("/etc/fonts/local.conf"));
+            match generic {
+                "serif" => self.set_serif_families(families),
+                "sans-serif" => self.set_sans_serif_families(families),
+                "monospace" => self.set_monospace_families(families),
+                "cursive" => self.set_cursive_families(families),
+                _ => self.set_fantasy_families(families),
             }
-            let _ = fontconfig.merge_config(Path::new("/etc/fonts/fonts.conf"));
         }
 
-        for fontconfig_parser::Alias {
-            alias,
-            default,
-            prefer,
-            accept,
-        } in fontconfig.aliases
-        {
-            let name = prefer
-                .get(0)
-                .or_else(|| accept.get(0))
-                .or_else(|| default.get(0));
-
-            if let Some(name) = name {
-                match alias.to_lowercase().as_str() {
-                    "serif" => self.set_serif_family(name),
-                    "sans-serif" => self.set_sans_serif_family(name),
-                    "sans serif" => self.set_sans_serif_family(name),
-                    "monospace" => self.set_monospace_family(name),
-                    "cursive" => self.set_cursive_family(name),
-                    "fantasy" => self.set_fantasy_family(name),
-                    _ => {}
-                }
-            }
+        let rules = fontconfig::MatchRules::new(&fontconfig.matches);
+        if !rules.is_empty() {
+            self.match_rules = Some(std::sync::Arc::new(rules));
         }
 
         if fontconfig.dirs.is_empty() {
             return false;
         }
 
-        let mut seen = Default::default();
-        for dir in fontconfig.dirs {
-            let path = if dir.path.starts_with("~") {
-                if let Ok(ref home) = home {
-                    Path::new(home).join(dir.path.strip_prefix("~").unwrap())
-                } else {
-                    continue;
-                }
-            } else {
-                dir.path
-            };
-            self.load_fonts_dir_impl(&path, &mut seen);
+        let mut options = self.scan_options.clone();
+        let rules = fontconfig::SelectRules::new(&fontconfig.select_fonts);
+        if !rules.is_empty() {
+            options.select_rules = Some(std::sync::Arc::new(rules));
         }
 
+        self.load_fonts_roots_with(&fontconfig.dirs, &options, report);
+
         true
     }
 
@@ -622,64 +1581,200 @@ impl Database {
 
     /// Sets the family that will be used by `Family::Serif`.
     pub fn set_serif_family<S: Into<String>>(&mut self, family: S) {
-        self.family_serif = family.into();
+        self.family_serif = vec![family.into()];
     }
 
     /// Sets the family that will be used by `Family::SansSerif`.
     pub fn set_sans_serif_family<S: Into<String>>(&mut self, family: S) {
-        self.family_sans_serif = family.into();
+        self.family_sans_serif = vec![family.into()];
     }
 
     /// Sets the family that will be used by `Family::Cursive`.
     pub fn set_cursive_family<S: Into<String>>(&mut self, family: S) {
-        self.family_cursive = family.into();
+        self.family_cursive = vec![family.into()];
     }
 
     /// Sets the family that will be used by `Family::Fantasy`.
     pub fn set_fantasy_family<S: Into<String>>(&mut self, family: S) {
-        self.family_fantasy = family.into();
+        self.family_fantasy = vec![family.into()];
     }
 
     /// Sets the family that will be used by `Family::Monospace`.
     pub fn set_monospace_family<S: Into<String>>(&mut self, family: S) {
-        self.family_monospace = family.into();
+        self.family_monospace = vec![family.into()];
+    }
+
+    /// Sets a prioritized list of families that will be used by `Family::Serif`.
+    pub fn set_serif_families<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, families: I) {
+        self.family_serif = families.into_iter().map(Into::into).collect();
+    }
+
+    /// Sets a prioritized list of families that will be used by `Family::SansSerif`.
+    pub fn set_sans_serif_families<I: IntoIterator<Item = S>, S: Into<String>>(
+        &mut self,
+        families: I,
+    ) {
+        self.family_sans_serif = families.into_iter().map(Into::into).collect();
+    }
+
+    /// Sets a prioritized list of families that will be used by `Family::Cursive`.
+    pub fn set_cursive_families<I: IntoIterator<Item = S>, S: Into<String>>(
+        &mut self,
+        families: I,
+    ) {
+        self.family_cursive = families.into_iter().map(Into::into).collect();
+    }
+
+    /// Sets a prioritized list of families that will be used by `Family::Fantasy`.
+    pub fn set_fantasy_families<I: IntoIterator<Item = S>, S: Into<String>>(
+        &mut self,
+        families: I,
+    ) {
+        self.family_fantasy = families.into_iter().map(Into::into).collect();
+    }
+
+    /// Sets a prioritized list of families that will be used by `Family::Monospace`.
+    pub fn set_monospace_families<I: IntoIterator<Item = S>, S: Into<String>>(
+        &mut self,
+        families: I,
+    ) {
+        self.family_monospace = families.into_iter().map(Into::into).collect();
     }
 
     /// Returns the generic family name or the `Family::Name` itself.
     ///
+    /// When a generic family has multiple names, returns the first one.
+    /// Returns an empty string when a generic family has no names.
+    ///
     /// Generic family names should be set via `Database::set_*_family` methods.
     pub fn family_name<'a>(&'a self, family: &'a Family) -> &'a str {
-        match family {
-            Family::Name(name) => name,
-            Family::Serif => self.family_serif.as_str(),
-            Family::SansSerif => self.family_sans_serif.as_str(),
-            Family::Cursive => self.family_cursive.as_str(),
-            Family::Fantasy => self.family_fantasy.as_str(),
-            Family::Monospace => self.family_monospace.as_str(),
-        }
+        self.family_names(family).next().unwrap_or("")
+    }
+
+    /// Returns all generic family names, in priority order, or the `Family::Name` itself.
+    ///
+    /// Generic family names should be set via `Database::set_*_families` methods.
+    pub fn family_names<'a>(&'a self, family: &'a Family) -> impl Iterator<Item = &'a str> + 'a {
+        let names: &[String] = match family {
+            Family::Name(name) => return FamilyNames::Single(Some(name)),
+            Family::Serif => &self.family_serif,
+            Family::SansSerif => &self.family_sans_serif,
+            Family::Cursive => &self.family_cursive,
+            Family::Fantasy => &self.family_fantasy,
+            Family::Monospace => &self.family_monospace,
+        };
+
+        FamilyNames::List(names.iter())
     }
 
     /// Performs a CSS-like query and returns the best matched font face.
+    ///
+    /// Generic families are resolved by trying each of their names in turn.
+    ///
+    /// On Linux, requested families are substituted according to fontconfig `<match>` rules
+    /// first, when they were loaded by `Database::load_system_fonts`.
+    ///
+    /// Android fallback families loaded by `Database::load_android_fonts_xml` are tried
+    /// before generic families when their language matches [`Query::lang`].
     pub fn query(&self, query: &Query) -> Option<ID> {
+        #[cfg(all(
+            unix,
+            feature = "fontconfig",
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        {
+            if let Some(ref rules) = self.match_rules {
+                return self.query_substituted(rules, query);
+            }
+        }
+
         for family in query.families {
-            let name = self.family_name(family);
-            let candidates: Vec<_> = self
-                .faces
-                .iter()
-                .filter(|(_, face)| face.families.iter().any(|family| family.0 == name))
-                .map(|(_, info)| info)
-                .collect();
+            if let Some(id) = self.query_family_names(family, query) {
+                return Some(id);
+            }
+        }
+
+        None
+    }
 
-            if !candidates.is_empty() {
-                if let Some(index) = find_best_match(&candidates, query) {
-                    return Some(candidates[index].id);
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    fn query_substituted(&self, rules: &fontconfig::MatchRules, query: &Query) -> Option<ID> {
+        let mut families: Vec<String> = query
+            .families
+            .iter()
+            .map(|family| match family {
+                Family::Name(name) => name.to_string(),
+                Family::Serif => "serif".to_string(),
+                Family::SansSerif => "sans-serif".to_string(),
+                Family::Cursive => "cursive".to_string(),
+                Family::Fantasy => "fantasy".to_string(),
+                Family::Monospace => "monospace".to_string(),
+            })
+            .collect();
+
+        rules.substitute(&mut families, query.lang);
+
+        for name in &families {
+            // Like in fontconfig, generic families are just names that have aliases.
+            let family = match name.to_ascii_lowercase().as_str() {
+                "serif" => Family::Serif,
+                "sans-serif" | "sans serif" => Family::SansSerif,
+                "cursive" => Family::Cursive,
+                "fantasy" => Family::Fantasy,
+                "monospace" => Family::Monospace,
+                _ => Family::Name(name),
+            };
+
+            if let Some(id) = self.query_family_names(&family, query) {
+                return Some(id);
+            }
+        }
+
+        None
+    }
+
+    fn query_family_names(&self, family: &Family, query: &Query) -> Option<ID> {
+        #[cfg(feature = "android")]
+        {
+            if let Some(lang) = query.lang.filter(|_| !matches!(family, Family::Name(_))) {
+                for (tags, name) in &self.lang_families {
+                    if android::lang_matches(tags, lang) {
+                        if let Some(id) = self.query_family(name, query) {
+                            return Some(id);
+                        }
+                    }
                 }
             }
         }
 
+        for name in self.family_names(family) {
+            if let Some(id) = self.query_family(name, query) {
+                return Some(id);
+            }
+        }
+
         None
     }
 
+    fn query_family(&self, name: &str, query: &Query) -> Option<ID> {
+        let candidates: Vec<_> = self
+            .faces
+            .iter()
+            .filter(|(_, face)| face.families.iter().any(|family| family.0 == name))
+            .map(|(_, info)| info)
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        find_best_match(&candidates, query).map(|index| candidates[index].id)
+    }
+
     /// Returns an iterator over the internal storage.
     ///
     /// This can be used for manual font matching.
@@ -710,6 +1805,14 @@ impl Database {
     ///
     /// In case of `Source::File`, the font file will be memory mapped.
     ///
+    /// Font containers, like WOFF or EOT, will be unpacked first.
+    /// For Mac suitcases (`.dfont`) and Windows font libraries (`.fon`), the closure receives
+    /// the face font data and a zero index.
+    ///
+    /// The closure receives a TrueType/OpenType font data only for [`FaceFormat::Sfnt`] faces.
+    /// Other formats, like Type 1 or bitmap fonts, are passed as is.
+    /// See [`FaceInfo::format`].
+    ///
     /// Returns `None` when font file loading failed.
     ///
     /// # Example
@@ -725,7 +1828,11 @@ impl Database {
         P: FnOnce(&[u8], u32) -> T,
     {
         let (src, face_index) = self.face_source(id)?;
-        src.with_data(|data| p(data, face_index))
+        src.with_data(|data| {
+            let (data, index) = face_font_data(data, face_index)?;
+            Some(p(data, index))
+        })
+        .flatten()
     }
 
     /// Makes the font data that backs the specified face id shared so that the application can
@@ -740,7 +1847,14 @@ impl Database {
     /// If the underlying font provides multiple faces, then all faces are updated to participate in
     /// the data sharing. If the face was previously marked for data sharing, then this function will
     /// return a clone of the existing reference.
+    ///
+    /// The returned data and face index are the same as passed to [`Self::with_face_data`].
+    /// Faces of Mac suitcases and Windows font libraries are returned as separate fonts,
+    /// which still share the whole file data.
+    ///
+    /// Faces from a [`Source::Custom`] and archive members are not updated.
     #[cfg(all(feature = "fs", feature = "memmap"))]
+    #[allow(clippy::type_complexity)]
     pub unsafe fn make_shared_face_data(
         &mut self,
         id: ID,
@@ -752,16 +1866,24 @@ impl Database {
 
         let (path, shared_data) = match &old_source {
             Source::Binary(data) => {
-                return Some((data.clone(), face_index));
+                return share_face_data(unpack_shared_data(data.clone())?, face_index);
             }
             Source::File(ref path) => {
                 let file = std::fs::File::open(path).ok()?;
-                let shared_data = std::sync::Arc::new(memmap2::MmapOptions::new().map(&file).ok()?)
-                    as std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>;
-                (path.clone(), shared_data)
+                let mmap = memmap2::MmapOptions::new().map(&file).ok()?;
+                // Containers cannot be shared as is, so we have to store the unpacked data instead.
+                (path.clone(), unpack_shared_data(std::sync::Arc::new(mmap))?)
             }
             Source::SharedFile(_, data) => {
-                return Some((data.clone(), face_index));
+                return share_face_data(unpack_shared_data(data.clone())?, face_index);
+            }
+            #[cfg(feature = "archive")]
+            Source::Archive(..) => {
+                let data = old_source.with_data(|data| data.to_vec())?;
+                return share_face_data(std::sync::Arc::new(data), face_index);
+            }
+            Source::Custom(provider) => {
+                return share_face_data(unpack_shared_data(provider.data()?)?, face_index);
             }
         };
 
@@ -773,12 +1895,14 @@ impl Database {
             }
         });
 
-        Some((shared_data, face_index))
+        share_face_data(shared_data, face_index)
     }
 
     /// Transfers ownership of shared font data back to the font database. This is the reverse operation
     /// of [`Self::make_shared_face_data`]. If the font data belonging to the specified face is mapped
     /// from a file on disk, then that mapping is closed and the data becomes private to the process again.
+    ///
+    /// Decompressed gzip files are not affected, since they are not mapped from disk.
     #[cfg(all(feature = "fs", feature = "memmap"))]
     pub fn make_face_data_unshared(&mut self, id: ID) {
         let face_info = match self.faces.get(id.0) {
@@ -794,6 +1918,15 @@ impl Database {
             _ => return,
         };
 
+        // Decompressed gzip files are not mapped, but kept in memory,
+        // since they cannot be loaded as `Source::File` without decompressing them on each access.
+        #[cfg(feature = "gzip")]
+        {
+            if is_gzip_file(&shared_path) {
+                return;
+            }
+        }
+
         let new_source = Source::File(shared_path.clone());
 
         self.faces.iter_mut().for_each(|(_, face)| {
@@ -809,9 +1942,14 @@ impl Database {
 /// A font can have multiple faces.
 ///
 /// A single item of the `Database`.
+///
+/// With the `serde` feature, `id` is not serialized and is set to a default value
+/// on deserialization. A new one will be assigned by [`Database::push_face_info`].
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FaceInfo {
     /// An unique ID.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub id: ID,
 
     /// A font source.
@@ -855,14 +1993,64 @@ pub struct FaceInfo {
 
     /// Indicates that the font face is monospaced.
     pub monospaced: bool,
+
+    /// A bitmap font face pixel size.
+    ///
+    /// `None` for scalable font faces.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pixel_size: Option<u16>,
+
+    /// A font face data format.
+    ///
+    /// Describes the data passed by [`Database::with_face_data`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: FaceFormat,
+}
+
+/// A font face data format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum FaceFormat {
+    /// A TrueType/OpenType font or a font collection.
+    ///
+    /// Includes fonts unpacked from containers, like WOFF or EOT, and Mac suitcases.
+    Sfnt,
+    /// A PostScript Type 1 font file, either PFB or PFA.
+    Type1,
+    /// An X11 PCF bitmap font.
+    Pcf,
+    /// An X11 BDF bitmap font.
+    Bdf,
+    /// A Windows FNT font, including ones from FON font libraries.
+    WinFnt,
+}
+
+impl FaceFormat {
+    /// Checks that the face data is a scalable TrueType/OpenType font.
+    #[inline]
+    pub fn is_scalable_sfnt(self) -> bool {
+        self == FaceFormat::Sfnt
+    }
+}
+
+impl Default for FaceFormat {
+    #[inline]
+    fn default() -> FaceFormat {
+        FaceFormat::Sfnt
+    }
 }
 
 /// A font source.
 ///
-/// Either a raw binary data or a file path.
+/// Either a raw binary data, a file path or a custom data provider.
 ///
 /// Stores the whole font and not just a single face.
+///
+/// With the `serde` feature, `File` and `SharedFile` are serialized as a path
+/// and deserialized as `File`, while `Binary` is serialized as bytes.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Source {
     /// A font's raw data, typically backed by a Vec<u8>.
     Binary(alloc::sync::Arc<dyn AsRef<[u8]> + Sync + Send>),
@@ -871,12 +2059,40 @@ pub enum Source {
     #[cfg(feature = "fs")]
     File(std::path::PathBuf),
 
-    /// A font's raw data originating from a shared file mapping.
-    #[cfg(all(feature = "fs", feature = "memmap"))]
+    /// A font's raw data originating from a file.
+    ///
+    /// Either a shared file mapping or a decompressed gzip file.
+    #[cfg(feature = "fs")]
     SharedFile(
         std::path::PathBuf,
         std::sync::Arc<dyn AsRef<[u8]> + Sync + Send>,
     ),
+
+    /// A font file inside a zip or tar archive.
+    ///
+    /// Contains the archive path, the member path inside the archive
+    /// and the member header offset, which is used to locate the member data,
+    /// since names are not necessarily unique.
+    #[cfg(feature = "archive")]
+    Archive(std::path::PathBuf, String, u64),
+
+    /// A font's data provided lazily by a custom provider.
+    Custom(alloc::sync::Arc<dyn FontDataProvider>),
+}
+
+/// A font data provider.
+///
+/// Allows loading fonts from custom storages, like asset packs or archives,
+/// without copying them into memory up front. See [`Source::Custom`].
+pub trait FontDataProvider: Send + Sync {
+    /// Returns the whole font data.
+    ///
+    /// Called each time the data is accessed, like during loading or by
+    /// [`Database::with_face_data`]. The returned data is dropped right after use,
+    /// so a provider doesn't have to keep it around.
+    ///
+    /// Font containers, like WOFF and WOFF2, will be unpacked by the caller.
+    fn data(&self) -> Option<alloc::sync::Arc<dyn AsRef<[u8]> + Sync + Send>>;
 }
 
 impl core::fmt::Debug for Source {
@@ -888,18 +2104,50 @@ impl core::fmt::Debug for Source {
                 .finish(),
             #[cfg(feature = "fs")]
             Self::File(arg0) => f.debug_tuple("File").field(arg0).finish(),
-            #[cfg(all(feature = "fs", feature = "memmap"))]
+            #[cfg(feature = "fs")]
             Self::SharedFile(arg0, arg1) => f
                 .debug_tuple("SharedFile")
                 .field(arg0)
                 .field(&arg1.as_ref().as_ref())
                 .finish(),
+            #[cfg(feature = "archive")]
+            Self::Archive(arg0, arg1, arg2) => f
+                .debug_tuple("Archive")
+                .field(arg0)
+                .field(arg1)
+                .field(arg2)
+                .finish(),
+            Self::Custom(_) => f.debug_tuple("Custom").finish(),
         }
     }
 }
 
-impl Source {
+impl Source {
+    /// Returns the font file path, if any.
+    #[cfg(feature = "fs")]
+    pub(crate) fn path(&self) -> Option<&std::path::Path> {
+        match self {
+            Source::Binary(_) | Source::Custom(_) => None,
+            Source::File(ref path) => Some(path),
+            Source::SharedFile(ref path, _) => Some(path),
+            #[cfg(feature = "archive")]
+            Source::Archive(ref path, ..) => Some(path),
+        }
+    }
+
+    /// Executes a closure with the source's data.
+    ///
+    /// Font containers, like WOFF and WOFF2, will be unpacked first.
     fn with_data<P, T>(&self, p: P) -> Option<T>
+    where
+        P: FnOnce(&[u8]) -> T,
+    {
+        self.with_raw_data(|data| with_unpacked_data(data, p))
+            .flatten()
+    }
+
+    /// Executes a closure with the source's data as is.
+    fn with_raw_data<P, T>(&self, p: P) -> Option<T>
     where
         P: FnOnce(&[u8]) -> T,
     {
@@ -918,8 +2166,304 @@ impl Source {
                 Some(p(data))
             }
             Source::Binary(ref data) => Some(p(data.as_ref().as_ref())),
-            #[cfg(all(feature = "fs", feature = "memmap"))]
+            #[cfg(feature = "fs")]
             Source::SharedFile(_, ref data) => Some(p(data.as_ref().as_ref())),
+            #[cfg(feature = "archive")]
+            Source::Archive(ref path, _, offset) => {
+                let data = read_archive(path).ok()?;
+                let offset = core::convert::TryFrom::try_from(*offset).ok()?;
+                let data = archive::entry_at(&data, offset)?.data(&data)?;
+
+                Some(p(&data))
+            }
+            Source::Custom(ref provider) => {
+                let data = provider.data()?;
+
+                Some(p(data.as_ref().as_ref()))
+            }
+        }
+    }
+}
+
+/// Maps an archive file into memory.
+#[cfg(all(feature = "archive", feature = "memmap"))]
+fn read_archive(path: &std::path::Path) -> std::io::Result<memmap2::Mmap> {
+    let file = std::fs::File::open(path)?;
+    unsafe { memmap2::MmapOptions::new().map(&file) }
+}
+
+/// Reads an archive file.
+#[cfg(all(feature = "archive", not(feature = "memmap")))]
+fn read_archive(path: &std::path::Path) -> std::io::Result<Vec<u8>> {
+    std::fs::read(path)
+}
+
+/// Checks that two faces have the same properties, ignoring IDs.
+#[cfg(feature = "fs")]
+fn is_same_face(a: &FaceInfo, b: &FaceInfo) -> bool {
+    a.index == b.index
+        && a.families == b.families
+        && a.post_script_name == b.post_script_name
+        && a.style == b.style
+        && a.weight == b.weight
+        && a.stretch == b.stretch
+        && a.monospaced == b.monospaced
+        && a.pixel_size == b.pixel_size
+        && a.format == b.format
+}
+
+/// Font faces parsed from a single font file.
+///
+/// `None` when the file is a malformed font container.
+#[cfg(feature = "fs")]
+type ParsedFile = Option<Vec<Result<FaceInfo, LoadError>>>;
+
+/// Reads and parses all font faces from a font file.
+#[cfg(feature = "fs")]
+fn parse_font_file(path: &std::path::Path) -> Result<ParsedFile, std::io::Error> {
+    #[cfg(feature = "memmap")]
+    let file = std::fs::File::open(path)?;
+    #[cfg(feature = "memmap")]
+    let data: &[u8] = unsafe { &memmap2::MmapOptions::new().map(&file)? };
+
+    #[cfg(not(feature = "memmap"))]
+    let data = &std::fs::read(path)?;
+
+    // Decompressed data is kept in memory, so it will not be decompressed on each access.
+    #[cfg(feature = "gzip")]
+    {
+        if ttf_parser::gzip::is_gzip(data) {
+            let data = match ttf_parser::gzip::decode(data) {
+                Some(data) => std::sync::Arc::new(data),
+                None => return Ok(None),
+            };
+
+            let source = Source::SharedFile(path.into(), data.clone());
+            return Ok(with_unpacked_data(&data, |unpacked| {
+                parse_faces(&source, &data, unpacked)
+            }));
+        }
+    }
+
+    let source = Source::File(path.into());
+    Ok(with_unpacked_data(data, |unpacked| {
+        parse_faces(&source, data, unpacked)
+    }))
+}
+
+/// Checks that a file starts with a gzip header.
+#[cfg(all(feature = "fs", feature = "memmap", feature = "gzip"))]
+fn is_gzip_file(path: &std::path::Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0; 3];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| ttf_parser::gzip::is_gzip(&header))
+}
+
+/// The number of bytes required by `is_font_signature`.
+#[cfg(feature = "fs")]
+const SIGNATURE_LEN: usize = 64;
+
+/// Checks that the data starts with a supported font signature.
+///
+/// Expects at least `SIGNATURE_LEN` bytes, unless the data is shorter.
+#[cfg(feature = "fs")]
+fn is_font_signature(data: &[u8]) -> bool {
+    if ttf_parser::is_sfnt(data) || ttf_parser::dfont::is_dfont(data) {
+        return true;
+    }
+
+    #[cfg(feature = "bitmap")]
+    {
+        if ttf_parser::x11::is_pcf(data) || ttf_parser::x11::is_bdf(data) {
+            return true;
+        }
+
+        // Windows bitmap fonts require more data. See `winfnt::signature_len`.
+        if ttf_parser::winfnt::is_fon(data) || ttf_parser::winfnt::is_fnt(data) {
+            return true;
+        }
+    }
+
+    #[cfg(feature = "eot")]
+    {
+        if ttf_parser::eot::is_eot(data) {
+            return true;
+        }
+    }
+
+    #[cfg(feature = "type1")]
+    {
+        if ttf_parser::type1::is_type1(data) {
+            return true;
+        }
+    }
+
+    #[cfg(feature = "woff")]
+    {
+        if ttf_parser::woff::is_woff(data) {
+            return true;
+        }
+    }
+
+    // Compressed data is checked by its decompressed signature.
+    #[cfg(feature = "gzip")]
+    {
+        if ttf_parser::gzip::is_gzip(data) {
+            return ttf_parser::gzip::decode_prefix(data, SIGNATURE_LEN)
+                .is_some_and(|data| !ttf_parser::gzip::is_gzip(&data) && is_font_signature(&data));
+        }
+    }
+
+    #[cfg(feature = "woff2")]
+    {
+        if ttf_parser::woff2::is_woff2(data) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Unpacks a font container into a raw TrueType/OpenType font data.
+///
+/// gzip-compressed data is decompressed as is, so it can be any supported font format.
+///
+/// Returns `None` when `data` is not a known font container
+/// and `Some(None)` when the container is malformed.
+#[allow(unused_variables)]
+fn unpack_font_data(data: &[u8]) -> Option<Option<Vec<u8>>> {
+    #[cfg(feature = "gzip")]
+    {
+        if ttf_parser::gzip::is_gzip(data) {
+            return Some(ttf_parser::gzip::decode(data));
+        }
+    }
+
+    #[cfg(feature = "woff")]
+    {
+        if ttf_parser::woff::is_woff(data) {
+            return Some(ttf_parser::woff::decode(data));
+        }
+    }
+
+    #[cfg(feature = "woff2")]
+    {
+        if ttf_parser::woff2::is_woff2(data) {
+            return Some(ttf_parser::woff2::decode(data));
+        }
+    }
+
+    // MicroType Express compressed fonts are not supported, so they are malformed as well.
+    #[cfg(feature = "eot")]
+    {
+        if ttf_parser::eot::is_eot(data) {
+            return Some(ttf_parser::eot::decode(data));
+        }
+    }
+
+    None
+}
+
+/// Executes a closure with the unpacked font data.
+///
+/// Returns `None` when the font container is malformed.
+fn with_unpacked_data<P, T>(data: &[u8], p: P) -> Option<T>
+where
+    P: FnOnce(&[u8]) -> T,
+{
+    match unpack_font_data(data) {
+        Some(data) => Some(p(&data?)),
+        None => Some(p(data)),
+    }
+}
+
+/// Returns the font data of a face inside a font file and the face index in it.
+///
+/// Font parsers support neither Mac suitcases nor Windows font libraries,
+/// so the face font is returned instead.
+fn face_font_data(data: &[u8], face_index: u32) -> Option<(&[u8], u32)> {
+    if ttf_parser::dfont::is_dfont(data) {
+        if let Some(data) = ttf_parser::dfont::sfnt_resource(data, face_index) {
+            return Some((data, 0));
+        }
+    }
+
+    #[cfg(feature = "bitmap")]
+    {
+        if let Some(data) = ttf_parser::winfnt::font_resource(data, face_index) {
+            return Some((data, 0));
+        }
+    }
+
+    Some((data, face_index))
+}
+
+/// Unpacks shared font data, when it's a font container.
+///
+/// Returns `None` when the font container is malformed.
+#[cfg(all(feature = "fs", feature = "memmap"))]
+fn unpack_shared_data(
+    data: std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>,
+) -> Option<std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>> {
+    match unpack_font_data((*data).as_ref()) {
+        Some(data) => Some(std::sync::Arc::new(data?)),
+        None => Some(data),
+    }
+}
+
+/// Returns shared face font data and the face index in it. See [`face_font_data`].
+#[cfg(all(feature = "fs", feature = "memmap"))]
+#[allow(clippy::type_complexity)]
+fn share_face_data(
+    data: std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>,
+    face_index: u32,
+) -> Option<(std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>, u32)> {
+    let file_data = (*data).as_ref();
+    let (face_data, index) = face_font_data(file_data, face_index)?;
+    let shared_data: std::sync::Arc<dyn AsRef<[u8]> + Send + Sync> =
+        if face_data.len() == file_data.len() {
+            data.clone()
+        } else {
+            let start = face_data.as_ptr() as usize - file_data.as_ptr() as usize;
+            std::sync::Arc::new(SharedFaceData {
+                data: data.clone(),
+                range: start..start + face_data.len(),
+            })
+        };
+
+    Some((shared_data, index))
+}
+
+/// A face font inside shared font file data.
+#[cfg(all(feature = "fs", feature = "memmap"))]
+struct SharedFaceData {
+    data: std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>,
+    range: core::ops::Range<usize>,
+}
+
+#[cfg(all(feature = "fs", feature = "memmap"))]
+impl AsRef<[u8]> for SharedFaceData {
+    fn as_ref(&self) -> &[u8] {
+        &(*self.data).as_ref()[self.range.clone()]
+    }
+}
+
+/// An iterator over family names returned by `Database::family_names`.
+enum FamilyNames<'a> {
+    Single(Option<&'a str>),
+    List(core::slice::Iter<'a, String>),
+}
+
+impl<'a> Iterator for FamilyNames<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            FamilyNames::Single(name) => name.take(),
+            FamilyNames::List(names) => names.next().map(String::as_str),
         }
     }
 }
@@ -948,6 +2492,12 @@ pub struct Query<'a> {
     ///
     /// [font-style](https://www.w3.org/TR/2018/REC-css-fonts-3-20180920/#font-style-prop) in CSS.
     pub style: Style,
+
+    /// A preferred language, like `ja` or `zh-TW`.
+    ///
+    /// Used to evaluate fontconfig `<match>` rules on Linux
+    /// and to select Android fallback families.
+    pub lang: Option<&'a str>,
 }
 
 // Enum value descriptions are from the CSS spec.
@@ -983,8 +2533,110 @@ pub enum Family<'a> {
     Monospace,
 }
 
+/// An owned version of [`Query`].
+///
+/// Can be used to store a query or to send it between processes.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct OwnedQuery {
+    /// A prioritized list of font family names or generic family names.
+    pub families: Vec<OwnedFamily>,
+
+    /// Specifies the weight of glyphs in the font.
+    pub weight: Weight,
+
+    /// Selects a normal, condensed, or expanded face from a font family.
+    pub stretch: Stretch,
+
+    /// Allows italic or oblique faces to be selected.
+    pub style: Style,
+
+    /// A preferred language, like `ja` or `zh-TW`.
+    pub lang: Option<String>,
+}
+
+impl OwnedQuery {
+    /// Executes a closure with a borrowed version of the query.
+    pub fn with_query<P, T>(&self, p: P) -> T
+    where
+        P: FnOnce(&Query) -> T,
+    {
+        let families: Vec<Family> = self.families.iter().map(OwnedFamily::as_family).collect();
+        p(&Query {
+            families: &families,
+            weight: self.weight,
+            stretch: self.stretch,
+            style: self.style,
+            lang: self.lang.as_deref(),
+        })
+    }
+}
+
+impl From<&Query<'_>> for OwnedQuery {
+    fn from(query: &Query) -> Self {
+        OwnedQuery {
+            families: query
+                .families
+                .iter()
+                .map(|f| OwnedFamily::from(*f))
+                .collect(),
+            weight: query.weight,
+            stretch: query.stretch,
+            style: query.style,
+            lang: query.lang.map(ToString::to_string),
+        }
+    }
+}
+
+/// An owned version of [`Family`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedFamily {
+    /// The name of a font family of choice.
+    Name(String),
+    /// Serif fonts.
+    Serif,
+    /// Sans-serif fonts.
+    SansSerif,
+    /// Cursive fonts.
+    Cursive,
+    /// Fantasy fonts.
+    Fantasy,
+    /// Monospace fonts.
+    Monospace,
+}
+
+impl OwnedFamily {
+    /// Returns a borrowed version of the family.
+    pub fn as_family(&self) -> Family<'_> {
+        match self {
+            OwnedFamily::Name(name) => Family::Name(name),
+            OwnedFamily::Serif => Family::Serif,
+            OwnedFamily::SansSerif => Family::SansSerif,
+            OwnedFamily::Cursive => Family::Cursive,
+            OwnedFamily::Fantasy => Family::Fantasy,
+            OwnedFamily::Monospace => Family::Monospace,
+        }
+    }
+}
+
+impl From<Family<'_>> for OwnedFamily {
+    fn from(family: Family) -> Self {
+        match family {
+            Family::Name(name) => OwnedFamily::Name(name.to_string()),
+            Family::Serif => OwnedFamily::Serif,
+            Family::SansSerif => OwnedFamily::SansSerif,
+            Family::Cursive => OwnedFamily::Cursive,
+            Family::Fantasy => OwnedFamily::Fantasy,
+            Family::Monospace => OwnedFamily::Monospace,
+        }
+    }
+}
+
 /// Specifies the weight of glyphs in the font, their degree of blackness or stroke thickness.
 #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weight(pub u16);
 
 impl Default for Weight {
@@ -1017,6 +2669,7 @@ impl Weight {
 
 /// Allows italic or oblique faces to be selected.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Style {
     /// A face that is neither italic not obliqued.
     Normal,
@@ -1033,7 +2686,54 @@ impl Default for Style {
     }
 }
 
+/// Parses all font faces from unpacked font data.
+///
+/// `raw` is the data before unpacking, which provides font container properties,
+/// like EOT headers.
+#[allow(unused_variables)]
+fn parse_faces(source: &Source, raw: &[u8], data: &[u8]) -> Vec<Result<FaceInfo, LoadError>> {
+    #[cfg(feature = "eot")]
+    {
+        if ttf_parser::eot::is_eot(raw) {
+            return vec![parse_eot_face_info(source.clone(), raw, data)];
+        }
+    }
+
+    let n = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+    (0..n)
+        .map(|index| parse_face_info(source.clone(), data, index))
+        .collect()
+}
+
 fn parse_face_info(source: Source, data: &[u8], index: u32) -> Result<FaceInfo, LoadError> {
+    #[cfg(feature = "type1")]
+    {
+        if ttf_parser::type1::is_type1(data) {
+            return parse_type1_face_info(source, data, index);
+        }
+    }
+
+    #[cfg(feature = "bitmap")]
+    {
+        if ttf_parser::x11::is_pcf(data) {
+            let props = ttf_parser::x11::parse_pcf(data);
+            return parse_x11_face_info(source, props, FaceFormat::Pcf, index);
+        } else if ttf_parser::x11::is_bdf(data) {
+            let props = ttf_parser::x11::parse_bdf(data);
+            return parse_x11_face_info(source, props, FaceFormat::Bdf, index);
+        } else if ttf_parser::winfnt::is_fon(data) {
+            let font = ttf_parser::winfnt::font_resource(data, index);
+            return parse_winfnt_face_info(source, font, index);
+        } else if ttf_parser::winfnt::is_fnt(data) {
+            let font = Some(data).filter(|_| index == 0);
+            return parse_winfnt_face_info(source, font, index);
+        }
+    }
+
+    parse_sfnt_face_info(source, data, index)
+}
+
+fn parse_sfnt_face_info(source: Source, data: &[u8], index: u32) -> Result<FaceInfo, LoadError> {
     let raw_face = ttf_parser::RawFace::parse(data, index).map_err(|_| LoadError::MalformedFont)?;
     let (families, post_script_name) = parse_names(&raw_face).ok_or(LoadError::UnnamedFont)?;
     let (mut style, weight, stretch) = parse_os2(&raw_face);
@@ -1053,6 +2753,8 @@ fn parse_face_info(source: Source, data: &[u8], index: u32) -> Result<FaceInfo,
         weight,
         stretch,
         monospaced,
+        pixel_size: None,
+        format: FaceFormat::Sfnt,
     })
 }
 
@@ -1144,6 +2846,249 @@ fn name_to_unicode(name: &ttf_parser::name::Name) -> Option<String> {
     }
 }
 
+#[cfg(feature = "type1")]
+fn parse_type1_face_info(source: Source, data: &[u8], index: u32) -> Result<FaceInfo, LoadError> {
+    if index != 0 {
+        return Err(LoadError::MalformedFont);
+    }
+
+    let info = ttf_parser::type1::parse(data).ok_or(LoadError::MalformedFont)?;
+    let family = match info.family_name {
+        Some(ref name) if !name.is_empty() => name.clone(),
+        // Like `Times-Bold`.
+        _ => info
+            .font_name
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+
+    let name = info.full_name.as_deref().unwrap_or(&info.font_name);
+    let style = if info.italic_angle == 0.0 {
+        Style::Normal
+    } else if contains_ignore_ascii_case(name, "oblique")
+        || contains_ignore_ascii_case(name, "slanted")
+    {
+        Style::Oblique
+    } else {
+        Style::Italic
+    };
+
+    let weight = info
+        .weight
+        .as_deref()
+        .and_then(weight_from_name)
+        .unwrap_or(Weight::NORMAL);
+
+    Ok(FaceInfo {
+        id: ID::dummy(),
+        source,
+        index,
+        families: vec![(family, Language::English_UnitedStates)],
+        post_script_name: info.font_name,
+        style,
+        weight,
+        stretch: Stretch::Normal,
+        monospaced: info.is_fixed_pitch,
+        pixel_size: None,
+        format: FaceFormat::Type1,
+    })
+}
+
+#[cfg(feature = "bitmap")]
+fn parse_x11_face_info(
+    source: Source,
+    props: Option<ttf_parser::x11::Properties>,
+    format: FaceFormat,
+    index: u32,
+) -> Result<FaceInfo, LoadError> {
+    let props = props.ok_or(LoadError::MalformedFont)?;
+    if index != 0 {
+        return Err(LoadError::MalformedFont);
+    }
+
+    let family = props
+        .family_name
+        .filter(|name| !name.is_empty())
+        .ok_or(LoadError::UnnamedFont)?;
+
+    // Unlike in other formats, `Medium` is the regular weight in X11 fonts.
+    let weight = match props.weight_name.as_deref() {
+        Some(name) if name.eq_ignore_ascii_case("medium") => Weight::NORMAL,
+        Some(name) => weight_from_name(name).unwrap_or(Weight::NORMAL),
+        None => Weight::NORMAL,
+    };
+
+    let style = match props
+        .slant
+        .as_deref()
+        .map(|s| s.to_ascii_uppercase())
+        .as_deref()
+    {
+        Some("I") | Some("RI") => Style::Italic,
+        Some("O") | Some("RO") => Style::Oblique,
+        _ => Style::Normal,
+    };
+
+    let stretch = props
+        .setwidth_name
+        .as_deref()
+        .and_then(stretch_from_name)
+        .unwrap_or(Stretch::Normal);
+
+    let monospaced = matches!(
+        props
+            .spacing
+            .as_deref()
+            .map(|s| s.to_ascii_uppercase())
+            .as_deref(),
+        Some("M") | Some("C")
+    );
+
+    Ok(FaceInfo {
+        id: ID::dummy(),
+        source,
+        index,
+        post_script_name: props.font.unwrap_or_else(|| family.clone()),
+        families: vec![(family, Language::English_UnitedStates)],
+        style,
+        weight,
+        stretch,
+        monospaced,
+        pixel_size: props.pixel_size,
+        format,
+    })
+}
+
+/// Parses an EOT font face from the header and the embedded font.
+#[cfg(feature = "eot")]
+fn parse_eot_face_info(source: Source, eot: &[u8], font: &[u8]) -> Result<FaceInfo, LoadError> {
+    let header = ttf_parser::eot::parse(eot).ok_or(LoadError::MalformedFont)?;
+
+    // The embedded font must be a plain TrueType/OpenType font, not another container.
+    if !ttf_parser::is_sfnt(font) {
+        return Err(LoadError::MalformedFont);
+    }
+
+    let mut info = parse_sfnt_face_info(source, font, 0)?;
+
+    if !header.family_name.is_empty() {
+        info.families
+            .retain(|(name, _)| *name != header.family_name);
+        info.families
+            .insert(0, (header.family_name, Language::English_UnitedStates));
+    }
+
+    if header.weight != 0 {
+        info.weight = Weight(header.weight);
+    }
+
+    if contains_ignore_ascii_case(&header.style_name, "oblique") {
+        info.style = Style::Oblique;
+    } else if header.italic {
+        info.style = Style::Italic;
+    }
+
+    Ok(info)
+}
+
+#[cfg(feature = "bitmap")]
+fn parse_winfnt_face_info(
+    source: Source,
+    font: Option<&[u8]>,
+    index: u32,
+) -> Result<FaceInfo, LoadError> {
+    let info = font
+        .and_then(ttf_parser::winfnt::parse_fnt)
+        .ok_or(LoadError::MalformedFont)?;
+    if info.face_name.is_empty() {
+        return Err(LoadError::UnnamedFont);
+    }
+
+    let weight = match info.weight {
+        0 => Weight::NORMAL,
+        n => Weight(n),
+    };
+
+    // Vector fonts are scalable.
+    let is_vector = info.is_vector;
+    let pixel_size = Some(info.pixel_height).filter(|size| *size != 0 && !is_vector);
+
+    Ok(FaceInfo {
+        id: ID::dummy(),
+        source,
+        index,
+        families: vec![(info.face_name.clone(), Language::English_UnitedStates)],
+        post_script_name: info.face_name,
+        style: if info.italic {
+            Style::Italic
+        } else {
+            Style::Normal
+        },
+        weight,
+        stretch: Stretch::Normal,
+        monospaced: info.is_fixed_pitch,
+        pixel_size,
+        format: FaceFormat::WinFnt,
+    })
+}
+
+/// Converts a width name, like `Condensed` or `Semi Expanded`, into a stretch.
+#[cfg(feature = "bitmap")]
+fn stretch_from_name(name: &str) -> Option<Stretch> {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let stretch = match name.as_str() {
+        "ultracondensed" => Stretch::UltraCondensed,
+        "extracondensed" => Stretch::ExtraCondensed,
+        "condensed" | "narrow" => Stretch::Condensed,
+        "semicondensed" => Stretch::SemiCondensed,
+        "normal" | "regular" => Stretch::Normal,
+        "semiexpanded" => Stretch::SemiExpanded,
+        "expanded" | "wide" => Stretch::Expanded,
+        "extraexpanded" => Stretch::ExtraExpanded,
+        "ultraexpanded" => Stretch::UltraExpanded,
+        _ => return None,
+    };
+
+    Some(stretch)
+}
+
+/// Converts a weight name, like `Bold` or `Semi Bold`, into a weight.
+#[cfg(any(feature = "type1", feature = "bitmap"))]
+fn weight_from_name(name: &str) -> Option<Weight> {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    let weight = match name.as_str() {
+        "thin" | "hairline" => Weight::THIN,
+        "extralight" | "ultralight" => Weight::EXTRA_LIGHT,
+        "light" => Weight::LIGHT,
+        "regular" | "normal" | "roman" | "book" | "plain" => Weight::NORMAL,
+        "medium" => Weight::MEDIUM,
+        "semibold" | "demibold" | "demi" => Weight::SEMIBOLD,
+        "bold" => Weight::BOLD,
+        "extrabold" | "ultrabold" | "heavy" => Weight::EXTRA_BOLD,
+        "black" | "ultra" | "ultrablack" | "extrablack" => Weight::BLACK,
+        _ => return None,
+    };
+
+    Some(weight)
+}
+
+#[cfg(any(feature = "type1", feature = "eot"))]
+fn contains_ignore_ascii_case(text: &str, needle: &str) -> bool {
+    text.to_ascii_lowercase().contains(needle)
+}
+
 fn parse_os2(raw_face: &ttf_parser::RawFace) -> (Style, Weight, Stretch) {
     const OS2_TAG: ttf_parser::Tag = ttf_parser::Tag::from_bytes(b"OS/2");
     let table = match raw_face
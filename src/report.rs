@@ -0,0 +1,140 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "fs")]
+use tinyvec::TinyVec;
+
+#[cfg(feature = "fs")]
+use crate::SkipReason;
+use crate::{LoadError, ID};
+
+/// A bulk font loading report.
+///
+/// Returned by [`Database::load_font_data`](crate::Database::load_font_data),
+/// [`Database::load_fonts_dir`](crate::Database::load_fonts_dir)
+/// and [`Database::load_system_fonts`](crate::Database::load_system_fonts).
+///
+/// All errors are still printed into the log as well.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct LoadReport {
+    /// IDs of the loaded font faces, in the loading order.
+    pub faces: Vec<ID>,
+
+    /// Loaded font files and IDs of the font faces loaded from each of them.
+    ///
+    /// Contains all successfully read files, even when no faces were loaded.
+    #[cfg(feature = "fs")]
+    pub files: Vec<(std::path::PathBuf, TinyVec<[ID; 8]>)>,
+
+    /// Fonts and font faces that failed to load.
+    pub errors: Vec<LoadFailure>,
+
+    /// Files that were skipped according to [`ScanOptions`](crate::ScanOptions).
+    #[cfg(feature = "fs")]
+    pub skipped: Vec<(std::path::PathBuf, SkipReason)>,
+
+    /// Directories that could not be read.
+    ///
+    /// Includes missing root directories, like a non-existing `~/.fonts`.
+    #[cfg(feature = "fs")]
+    pub unreadable_dirs: Vec<(std::path::PathBuf, std::io::Error)>,
+
+    /// Scanned root directories and the time spent on each of them.
+    #[cfg(feature = "fs")]
+    pub roots: Vec<(std::path::PathBuf, std::time::Duration)>,
+}
+
+impl LoadReport {
+    /// Checks that nothing has failed.
+    ///
+    /// Skipped files are not considered failures.
+    pub fn is_ok(&self) -> bool {
+        #[cfg(feature = "fs")]
+        {
+            if !self.unreadable_dirs.is_empty() {
+                return false;
+            }
+        }
+
+        self.errors.is_empty()
+    }
+}
+
+/// A font loading failure.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LoadFailure {
+    /// A font file path.
+    ///
+    /// `None` for fonts loaded from binary data.
+    #[cfg(feature = "fs")]
+    pub path: Option<std::path::PathBuf>,
+
+    /// A face index in a font collection.
+    ///
+    /// `None` when the whole font failed to load.
+    pub index: Option<u32>,
+
+    /// The error.
+    pub error: LoadError,
+}
+
+impl core::fmt::Display for LoadFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        #[cfg(feature = "fs")]
+        {
+            if let Some(ref path) = self.path {
+                write!(f, "'{}'", path.display())?;
+            } else {
+                write!(f, "binary data")?;
+            }
+        }
+
+        #[cfg(not(feature = "fs"))]
+        {
+            write!(f, "binary data")?;
+        }
+
+        if let Some(index) = self.index {
+            write!(f, " face {}", index)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl LoadFailure {
+    #[cfg_attr(not(feature = "fs"), allow(unused_variables))]
+    pub(crate) fn new(source: &crate::Source, index: Option<u32>, error: LoadError) -> Self {
+        LoadFailure {
+            #[cfg(feature = "fs")]
+            path: source.path().map(|p| p.to_path_buf()),
+            index,
+            error,
+        }
+    }
+}
+
+/// A [`Database::rescan`](crate::Database::rescan) report.
+#[cfg(feature = "fs")]
+#[derive(Debug, Default)]
+pub struct RescanReport {
+    /// IDs of font faces from new files and new faces from changed files.
+    pub added: Vec<ID>,
+
+    /// IDs of font faces from deleted files and faces that no longer exist in changed files.
+    ///
+    /// These IDs are no longer valid.
+    pub removed: Vec<ID>,
+
+    /// IDs of font faces from changed files which properties have changed.
+    ///
+    /// Such faces keep their IDs, while `FaceInfo` is updated.
+    pub changed: Vec<ID>,
+
+    /// New and changed files loading details.
+    ///
+    /// `faces` contains both added and changed faces.
+    pub load: LoadReport,
+}
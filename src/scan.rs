@@ -0,0 +1,647 @@
+use std::path::Path;
+
+/// Directory scanning options.
+///
+/// Used by [`Database::load_fonts_dir`](crate::Database::load_fonts_dir)
+/// and [`Database::load_system_fonts`](crate::Database::load_system_fonts).
+/// Can be set via [`Database::set_scan_options`](crate::Database::set_scan_options)
+/// or passed directly to [`Database::load_fonts_dir_with`](crate::Database::load_fonts_dir_with).
+///
+/// By default, only files with known font extensions will be loaded.
+/// Extensions are matched case-insensitively.
+/// Directories are scanned recursively without a depth limit and symlinks are followed.
+///
+/// # Example
+///
+/// ```
+/// let options = fontdb::ScanOptions::new()
+///     .sniff_content(true)
+///     .deny_extension("pdf")
+///     .max_depth(2)
+///     .symlinks(fontdb::SymlinkPolicy::WithinRoot)
+///     .skip_hidden_dirs(true)
+///     .exclude("**/node_modules");
+///
+/// let mut db = fontdb::Database::new();
+/// db.load_fonts_dir_with("assets", &options);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScanOptions {
+    sniff_content: bool,
+    allowed_extensions: Vec<String>,
+    denied_extensions: Vec<String>,
+    max_depth: Option<usize>,
+    symlinks: SymlinkPolicy,
+    skip_hidden_dirs: bool,
+    excludes: Vec<String>,
+    track_changes: bool,
+    #[cfg(all(
+        unix,
+        feature = "fontconfig",
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    pub(crate) select_rules: Option<std::sync::Arc<crate::fontconfig::SelectRules>>,
+}
+
+/// A symlinks handling policy.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SymlinkPolicy {
+    /// Follow all symlinks.
+    ///
+    /// Files and directories that were already visited will be skipped.
+    #[default]
+    Follow,
+    /// Ignore symlinks completely.
+    Skip,
+    /// Follow only symlinks that point inside the scanned root directory.
+    WithinRoot,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanOptions {
+    /// Creates new scanning options.
+    ///
+    /// Allows `ttf`, `ttc`, `otf`, `otc` and `dfont` extensions,
+    /// plus `woff`, `woff2`, `pfb`, `pfa`, `pcf`, `bdf`, `fon`, `fnt` and `eot`
+    /// when the corresponding features are enabled.
+    ///
+    /// With the `gzip` feature, compressed files, like `font.pcf.gz`,
+    /// are checked by the extension before `.gz`.
+    ///
+    /// Denies some extensions that are typically present in fonts directories,
+    /// like `txt`, `afm` or `uuid`.
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut allowed_extensions = vec![
+            "ttf".to_string(),
+            "ttc".to_string(),
+            "otf".to_string(),
+            "otc".to_string(),
+            "dfont".to_string(),
+        ];
+
+        #[cfg(feature = "woff")]
+        allowed_extensions.push("woff".to_string());
+        #[cfg(feature = "woff2")]
+        allowed_extensions.push("woff2".to_string());
+        #[cfg(feature = "type1")]
+        allowed_extensions.extend(["pfb".to_string(), "pfa".to_string()]);
+        #[cfg(feature = "bitmap")]
+        allowed_extensions.extend(["pcf", "bdf", "fon", "fnt"].map(String::from));
+        #[cfg(feature = "eot")]
+        allowed_extensions.push("eot".to_string());
+
+        #[rustfmt::skip]
+        let denied_extensions = [
+            "txt", "md", "html", "pdf", "xml", "json", "conf",
+            "afm", "pfm", "uuid", "dir", "scale", "alias",
+        ];
+
+        ScanOptions {
+            sniff_content: false,
+            allowed_extensions,
+            denied_extensions: denied_extensions.iter().map(|e| e.to_string()).collect(),
+            max_depth: None,
+            symlinks: SymlinkPolicy::Follow,
+            skip_hidden_dirs: false,
+            excludes: Vec::new(),
+            track_changes: false,
+            #[cfg(all(
+                unix,
+                feature = "fontconfig",
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            ))]
+            select_rules: None,
+        }
+    }
+
+    /// Enables content sniffing.
+    ///
+    /// When enabled, files with an extension that is neither allowed nor denied,
+    /// including files without an extension, will be loaded when their content
+    /// starts with a supported font signature.
+    ///
+    /// This requires reading the first bytes of each file, therefore it's slower.
+    /// Windows FON and FNT fonts are recognized by offsets and sizes stored in them,
+    /// so up to 64 KiB can be read for them. Larger ones are not recognized.
+    ///
+    /// Disabled by default.
+    pub fn sniff_content(mut self, sniff: bool) -> Self {
+        self.sniff_content = sniff;
+        self
+    }
+
+    /// Adds an extension to the allowed list.
+    ///
+    /// Files with allowed extensions will always be loaded, without content sniffing.
+    ///
+    /// The extension should be passed without a leading dot.
+    pub fn allow_extension(mut self, ext: &str) -> Self {
+        let ext = ext.to_ascii_lowercase();
+        self.denied_extensions.retain(|e| *e != ext);
+        if !self.allowed_extensions.contains(&ext) {
+            self.allowed_extensions.push(ext);
+        }
+
+        self
+    }
+
+    /// Replaces the allowed extensions list.
+    ///
+    /// Extensions should be passed without a leading dot.
+    pub fn extensions<'a, I: IntoIterator<Item = &'a str>>(mut self, exts: I) -> Self {
+        self.allowed_extensions.clear();
+        for ext in exts {
+            self = self.allow_extension(ext);
+        }
+
+        self
+    }
+
+    /// Adds an extension to the denied list.
+    ///
+    /// Files with denied extensions will always be skipped, without content sniffing.
+    ///
+    /// The extension should be passed without a leading dot.
+    pub fn deny_extension(mut self, ext: &str) -> Self {
+        let ext = ext.to_ascii_lowercase();
+        self.allowed_extensions.retain(|e| *e != ext);
+        if !self.denied_extensions.contains(&ext) {
+            self.denied_extensions.push(ext);
+        }
+
+        self
+    }
+
+    /// Sets the maximum directory recursion depth.
+    ///
+    /// `0` means that only files directly inside the scanned directory will be loaded.
+    ///
+    /// Unlimited by default.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Sets the symlinks handling policy.
+    ///
+    /// [`SymlinkPolicy::Follow`] by default.
+    pub fn symlinks(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    /// Skips directories which names start with a dot.
+    ///
+    /// Disabled by default.
+    pub fn skip_hidden_dirs(mut self, skip: bool) -> Self {
+        self.skip_hidden_dirs = skip;
+        self
+    }
+
+    /// Adds a glob pattern of files and directories that should be skipped.
+    ///
+    /// Patterns containing a `/` are matched against a path relative to the scanned
+    /// root directory, using `/` as a separator. Other patterns are matched against
+    /// a file name only.
+    ///
+    /// Supports `?`, `*`, `**` and `[...]` wildcards. Only `**` can match a `/`.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.excludes.push(pattern.to_string());
+        self
+    }
+
+    /// Remembers sizes and modification times of loaded files,
+    /// so [`Database::rescan`](crate::Database::rescan) could skip unchanged files.
+    ///
+    /// This requires an additional `stat` call for each file, therefore it's slower.
+    /// Without it, `rescan` will parse all previously loaded files again.
+    /// Sizes and modification times are always remembered when the cache is enabled.
+    ///
+    /// Disabled by default.
+    pub fn track_changes(mut self, track: bool) -> Self {
+        self.track_changes = track;
+        self
+    }
+
+    /// Checks that file changes should be tracked.
+    pub(crate) fn tracks_changes(&self) -> bool {
+        self.track_changes
+    }
+
+    /// Checks that a directory at the specified depth should be scanned.
+    ///
+    /// The root directory has a depth of `0`.
+    pub(crate) fn accepts_dir(&self, path: &Path, depth: usize) -> bool {
+        if self.max_depth.is_some_and(|max| depth > max) {
+            return false;
+        }
+
+        if self.skip_hidden_dirs
+            && path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Checks that a path is excluded via glob patterns.
+    ///
+    /// `path` must be relative to the scanned root directory.
+    pub(crate) fn is_excluded(&self, path: &Path) -> bool {
+        if self.excludes.is_empty() {
+            return false;
+        }
+
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => return false,
+        };
+
+        let mut relative = String::new();
+        for component in path.components() {
+            if !relative.is_empty() {
+                relative.push('/');
+            }
+            relative.push_str(&component.as_os_str().to_string_lossy());
+        }
+
+        self.excludes.iter().any(|pattern| {
+            if pattern.contains('/') {
+                crate::glob::glob_match(pattern, &relative)
+            } else {
+                crate::glob::glob_match(pattern, &name)
+            }
+        })
+    }
+
+    /// Checks that a font face is rejected by fontconfig rules.
+    #[allow(unused_variables)]
+    pub(crate) fn rejects_face(&self, face: &crate::FaceInfo) -> bool {
+        #[cfg(all(
+            unix,
+            feature = "fontconfig",
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        {
+            if let Some(ref rules) = self.select_rules {
+                return rules.rejects_face(face);
+            }
+        }
+
+        false
+    }
+
+    /// Checks that a file at `path` should be loaded.
+    pub(crate) fn check_file(&self, path: &Path) -> Result<(), SkipReason> {
+        #[cfg(all(
+            unix,
+            feature = "fontconfig",
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        {
+            if let Some(ref rules) = self.select_rules {
+                if rules.rejects_file(path) {
+                    return Err(SkipReason::Rejected);
+                }
+            }
+        }
+
+        if let Some(ext) = font_extension(path) {
+            if contains_ignore_case(&self.denied_extensions, ext) {
+                return Err(SkipReason::DeniedExtension);
+            }
+
+            if contains_ignore_case(&self.allowed_extensions, ext) {
+                return Ok(());
+            }
+        }
+
+        if !self.sniff_content {
+            return Err(SkipReason::UnknownExtension);
+        }
+
+        if sniff_file(path) {
+            Ok(())
+        } else {
+            Err(SkipReason::NotAFont)
+        }
+    }
+}
+
+/// A scanned root directory.
+pub(crate) struct ScanRoot<'a> {
+    pub options: &'a ScanOptions,
+    /// A canonical root path. Used by [`SymlinkPolicy::WithinRoot`].
+    pub canonical_path: std::path::PathBuf,
+}
+
+impl<'a> ScanRoot<'a> {
+    pub fn new(path: &Path, options: &'a ScanOptions) -> Self {
+        ScanRoot {
+            options,
+            canonical_path: std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+        }
+    }
+
+    #[inline]
+    pub fn symlinks(&self) -> SymlinkPolicy {
+        self.options.symlinks
+    }
+}
+
+/// A directory entry found by [`walk_roots`].
+pub(crate) struct WalkEntry {
+    /// A path returned by `read_dir`.
+    pub path: std::path::PathBuf,
+    /// An entry type. Symlinks are not followed.
+    pub file_type: std::fs::FileType,
+    /// A canonical symlink target path.
+    ///
+    /// `None` for regular entries and for symlinks that should not be followed.
+    pub target: Option<std::path::PathBuf>,
+    pub kind: WalkKind,
+}
+
+pub(crate) enum WalkKind {
+    /// Matches an exclude pattern.
+    Excluded,
+    /// A file and the result of [`ScanOptions::check_file`].
+    File(Result<(), SkipReason>),
+    /// A directory and its entries.
+    Dir(Result<Vec<WalkEntry>, std::io::Error>),
+    /// A directory that should not be scanned, a broken symlink or a special file.
+    Other,
+}
+
+/// A walked root directory.
+pub(crate) struct WalkedRoot {
+    pub entries: Result<Vec<WalkEntry>, std::io::Error>,
+    /// Time spent on walking.
+    pub elapsed: std::time::Duration,
+}
+
+/// Walks root directories recursively.
+///
+/// Roots are walked concurrently when the `parallel` feature is enabled.
+///
+/// Files are not deduplicated across roots here, otherwise the result would depend
+/// on the walking order. Symlinked directories are visited only once per root,
+/// which is enough to stop at symlink loops. The caller is responsible
+/// for skipping already visited files and directories, in the roots order.
+pub(crate) fn walk_roots(roots: &[(&Path, &ScanOptions)]) -> Vec<WalkedRoot> {
+    let walk = |(dir, options): &(&Path, &ScanOptions)| {
+        let now = std::time::Instant::now();
+        let root = ScanRoot::new(dir, options);
+        let mut visited = std::collections::HashSet::new();
+        let entries = walk_dir(dir, "".as_ref(), &root, &mut visited);
+        WalkedRoot {
+            entries,
+            elapsed: now.elapsed(),
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        roots.par_iter().map(walk).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        roots.iter().map(walk).collect()
+    }
+}
+
+fn walk_dir(
+    dir: &Path,
+    relative: &Path,
+    root: &ScanRoot,
+    visited: &mut std::collections::HashSet<std::path::PathBuf>,
+) -> Result<Vec<WalkEntry>, std::io::Error> {
+    let mut entries = Vec::new();
+    let depth = relative.components().count();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let relative = relative.join(entry.file_name());
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(_) => continue,
+        };
+
+        let path = entry.path();
+        if root.options.is_excluded(&relative) {
+            entries.push(WalkEntry {
+                path,
+                file_type,
+                target: None,
+                kind: WalkKind::Excluded,
+            });
+            continue;
+        }
+
+        let (target, target_type) = if file_type.is_symlink() {
+            if root.symlinks() == SymlinkPolicy::Skip {
+                continue;
+            }
+
+            match resolve_symlink(&path, root) {
+                Some((target, target_type)) => (Some(target), Some(target_type)),
+                None => (None, None),
+            }
+        } else {
+            (None, Some(file_type))
+        };
+
+        let resolved = target.as_deref().unwrap_or(&path);
+        let kind = match target_type {
+            Some(t) if t.is_file() => WalkKind::File(root.options.check_file(resolved)),
+            Some(t) if t.is_dir() && root.options.accepts_dir(&relative, depth + 1) => {
+                if target.is_some() && !visited.insert(resolved.to_path_buf()) {
+                    WalkKind::Other
+                } else {
+                    WalkKind::Dir(walk_dir(resolved, &relative, root, visited))
+                }
+            }
+            _ => WalkKind::Other,
+        };
+
+        entries.push(WalkEntry {
+            path,
+            file_type,
+            target,
+            kind,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Returns a canonical symlink target path and type.
+fn resolve_symlink(
+    path: &Path,
+    root: &ScanRoot,
+) -> Option<(std::path::PathBuf, std::fs::FileType)> {
+    let stat = std::fs::metadata(path).ok()?;
+    if stat.is_symlink() {
+        return None;
+    }
+
+    let canon = std::fs::canonicalize(path).ok()?;
+    if root.symlinks() == SymlinkPolicy::WithinRoot && !canon.starts_with(&root.canonical_path) {
+        return None;
+    }
+
+    Some((canon, stat.file_type()))
+}
+
+/// Scanned root directories and files loaded from them.
+///
+/// Used by [`Database::rescan`](crate::Database::rescan).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ScanState {
+    /// Root directories and options they were scanned with, in the loading order.
+    pub roots: Vec<(std::path::PathBuf, ScanOptions)>,
+    pub files: std::collections::HashMap<std::path::PathBuf, ScannedFile>,
+}
+
+impl ScanState {
+    pub fn add_root(&mut self, dir: &Path, options: &ScanOptions) {
+        match self.roots.iter_mut().find(|(path, _)| path == dir) {
+            Some(root) => root.1 = options.clone(),
+            None => self.roots.push((dir.to_path_buf(), options.clone())),
+        }
+    }
+}
+
+/// A font file loaded via directory scanning.
+#[derive(Clone, Debug)]
+pub(crate) struct ScannedFile {
+    pub stamp: Option<FileStamp>,
+    pub ids: tinyvec::TinyVec<[crate::ID; 8]>,
+}
+
+/// File metadata used to detect changes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct FileStamp {
+    pub len: u64,
+    pub modified: Option<std::time::SystemTime>,
+}
+
+impl FileStamp {
+    pub fn new(path: &Path) -> Option<Self> {
+        let meta = std::fs::metadata(path).ok()?;
+        Some(FileStamp {
+            len: meta.len(),
+            modified: meta.modified().ok(),
+        })
+    }
+}
+
+/// A reason why a file was skipped during directory scanning.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// A file extension is in the denied list.
+    DeniedExtension,
+    /// A file extension is not in the allowed list and content sniffing is disabled.
+    UnknownExtension,
+    /// A file content doesn't start with a supported font signature.
+    NotAFont,
+    /// A file matches an exclude pattern.
+    Excluded,
+    /// A file or a font face is rejected by a fontconfig `<selectfont>` rule.
+    Rejected,
+}
+
+impl core::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SkipReason::DeniedExtension => write!(f, "denied extension"),
+            SkipReason::UnknownExtension => write!(f, "unknown extension"),
+            SkipReason::NotAFont => write!(f, "not a font"),
+            SkipReason::Excluded => write!(f, "excluded"),
+            SkipReason::Rejected => write!(f, "rejected by fontconfig"),
+        }
+    }
+}
+
+/// Returns the file extension.
+///
+/// For gzip-compressed files, like `font.pcf.gz`, returns the inner extension.
+/// `None` when a compressed file has no inner extension.
+fn font_extension(path: &Path) -> Option<&str> {
+    let ext = path.extension()?.to_str()?;
+
+    #[cfg(feature = "gzip")]
+    {
+        if ext.eq_ignore_ascii_case("gz") {
+            let stem = Path::new(path.file_stem()?);
+            return stem.extension()?.to_str();
+        }
+    }
+
+    Some(ext)
+}
+
+#[inline]
+fn contains_ignore_case(list: &[String], ext: &str) -> bool {
+    list.iter().any(|e| e.eq_ignore_ascii_case(ext))
+}
+
+/// The number of additional bytes read to sniff gzip-compressed files.
+#[cfg(feature = "gzip")]
+const GZIP_SNIFF_LEN: u64 = 4096;
+
+/// The maximum number of bytes read to sniff Windows bitmap fonts.
+#[cfg(feature = "bitmap")]
+const WINFNT_SNIFF_LEN: usize = 64 * 1024;
+
+fn sniff_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(crate::SIGNATURE_LEN);
+    let res = std::fs::File::open(path).and_then(|mut file| {
+        (&mut file)
+            .take(crate::SIGNATURE_LEN as u64)
+            .read_to_end(&mut header)?;
+
+        // Font libraries and FNT fonts are recognized by offsets and sizes stored in them.
+        #[cfg(feature = "bitmap")]
+        {
+            while let Some(len) = crate::ttf_parser::winfnt::signature_len(&header) {
+                if len <= header.len() || len > WINFNT_SNIFF_LEN {
+                    break;
+                }
+
+                let missing = (len - header.len()) as u64;
+                if (&mut file).take(missing).read_to_end(&mut header)? == 0 {
+                    break;
+                }
+            }
+        }
+
+        // The compressed signature is usually way shorter than this,
+        // but gzip headers can contain a file name and a comment.
+        #[cfg(feature = "gzip")]
+        {
+            if crate::ttf_parser::gzip::is_gzip(&header) {
+                file.take(GZIP_SNIFF_LEN).read_to_end(&mut header)?;
+            }
+        }
+
+        Ok(())
+    });
+    if res.is_err() {
+        return false;
+    }
+
+    crate::is_font_signature(&header)
+}
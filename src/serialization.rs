@@ -0,0 +1,168 @@
+//! `serde` support for types that cannot derive it.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::Source;
+
+const SOURCE_VARIANTS: &[&str] = &["Binary", "File", "Archive"];
+
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v))
+            }
+
+            // Self-describing formats, like JSON, store bytes as a sequence.
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    data.push(byte);
+                }
+
+                Ok(ByteBuf(data))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Source::Binary(data) => serializer.serialize_newtype_variant(
+                "Source",
+                0,
+                "Binary",
+                &Bytes((**data).as_ref()),
+            ),
+            #[cfg(feature = "fs")]
+            Source::File(path) => serializer.serialize_newtype_variant("Source", 1, "File", path),
+            // The mapping itself cannot be shared between processes.
+            #[cfg(feature = "fs")]
+            Source::SharedFile(path, _) => {
+                serializer.serialize_newtype_variant("Source", 1, "File", path)
+            }
+            #[cfg(feature = "archive")]
+            Source::Archive(path, name, offset) => {
+                serializer.serialize_newtype_variant("Source", 2, "Archive", &(path, name, offset))
+            }
+            Source::Custom(_) => Err(serde::ser::Error::custom(
+                "custom font sources cannot be serialized",
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        enum Variant {
+            Binary,
+            File,
+            Archive,
+        }
+
+        impl<'de> Deserialize<'de> for Variant {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct VariantVisitor;
+
+                impl<'de> Visitor<'de> for VariantVisitor {
+                    type Value = Variant;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str("a font source variant")
+                    }
+
+                    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                        match v {
+                            0 => Ok(Variant::Binary),
+                            1 => Ok(Variant::File),
+                            2 => Ok(Variant::Archive),
+                            _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                        }
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                        match v {
+                            "Binary" => Ok(Variant::Binary),
+                            "File" => Ok(Variant::File),
+                            "Archive" => Ok(Variant::Archive),
+                            _ => Err(E::unknown_variant(v, SOURCE_VARIANTS)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(VariantVisitor)
+            }
+        }
+
+        struct SourceVisitor;
+
+        impl<'de> Visitor<'de> for SourceVisitor {
+            type Value = Source;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a font source")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+                let (variant, access) = data.variant()?;
+                match variant {
+                    Variant::Binary => {
+                        let data: ByteBuf = access.newtype_variant()?;
+                        Ok(Source::Binary(Arc::new(data.0)))
+                    }
+                    #[cfg(feature = "fs")]
+                    Variant::File => Ok(Source::File(access.newtype_variant()?)),
+                    #[cfg(not(feature = "fs"))]
+                    Variant::File => {
+                        let _ = access;
+                        Err(de::Error::custom("file sources require the `fs` feature"))
+                    }
+                    #[cfg(feature = "archive")]
+                    Variant::Archive => {
+                        let (path, name, offset) = access.newtype_variant()?;
+                        Ok(Source::Archive(path, name, offset))
+                    }
+                    #[cfg(not(feature = "archive"))]
+                    Variant::Archive => {
+                        let _ = access;
+                        Err(de::Error::custom(
+                            "archive sources require the `archive` feature",
+                        ))
+                    }
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Source", SOURCE_VARIANTS, SourceVisitor)
+    }
+}
@@ -0,0 +1,90 @@
+//! A [Mac resource fork](https://developer.apple.com/library/archive/documentation/mac/pdf/MoreMacintoshToolbox.pdf)
+//! fonts suitcase, also known as `.dfont`, parsing.
+//!
+//! Only `sfnt` resources are supported. Each of them is a regular TrueType/OpenType font.
+
+use super::parser::{NumFrom, Stream};
+use super::Tag;
+
+const SFNT_TYPE: Tag = Tag::from_bytes(b"sfnt");
+const HEADER_SIZE: usize = 16;
+/// Resources data always starts right after the header and the reserved space.
+const DATA_OFFSET: u32 = 256;
+
+/// Checks that the data starts with a resource fork header.
+///
+/// Requires only the first 16 bytes.
+pub fn is_dfont(data: &[u8]) -> bool {
+    let mut s = Stream::new(data);
+    let (data_offset, map_offset, data_length, map_length) = match (
+        s.read::<u32>(),
+        s.read::<u32>(),
+        s.read::<u32>(),
+        s.read::<u32>(),
+    ) {
+        (Some(a), Some(b), Some(c), Some(d)) => (a, b, c, d),
+        _ => return false,
+    };
+
+    // The resource map always follows the resources data.
+    data_offset == DATA_OFFSET
+        && data_offset.checked_add(data_length) == Some(map_offset)
+        && map_length != 0
+}
+
+/// Returns the number of `sfnt` resources.
+pub fn fonts_in_dfont(data: &[u8]) -> Option<u32> {
+    let (_, count) = sfnt_references(data)?;
+    Some(u32::from(count))
+}
+
+/// Returns the data of an `sfnt` resource by index.
+pub fn sfnt_resource(data: &[u8], index: u32) -> Option<&[u8]> {
+    let (references, count) = sfnt_references(data)?;
+    if index >= u32::from(count) {
+        return None;
+    }
+
+    // Each reference is: ID (u16), name offset (u16), attributes (u8),
+    // data offset (u24) and a reserved handle (u32).
+    let mut s = Stream::new(references.get(index as usize * 12..)?);
+    s.advance(4);
+    let offset = s.read::<u32>()? & 0x00FF_FFFF;
+
+    let data_offset = Stream::read_at::<u32>(data, 0)?;
+    let start = usize::num_from(data_offset.checked_add(offset)?);
+    let mut s = Stream::new(data.get(start..)?);
+    // The length is not trusted, so it's checked before reading.
+    let length = usize::num_from(s.read::<u32>()?);
+    s.tail()?.get(..length)
+}
+
+/// Returns the `sfnt` resources reference list and the number of resources in it.
+fn sfnt_references(data: &[u8]) -> Option<(&[u8], u16)> {
+    if !is_dfont(data) {
+        return None;
+    }
+
+    let map_offset = usize::num_from(Stream::read_at::<u32>(data, 4)?);
+    let map = data.get(map_offset..)?;
+
+    let mut s = Stream::new(map);
+    // A header copy, next map handle, file reference number and attributes.
+    s.advance(HEADER_SIZE + 4 + 2 + 2);
+    let type_list_offset = usize::from(s.read::<u16>()?);
+
+    let type_list = map.get(type_list_offset..)?;
+    let mut s = Stream::new(type_list);
+    // The number of types minus one. 0xFFFF means that there are no types.
+    let types_count = s.read::<u16>()?.wrapping_add(1);
+    for _ in 0..types_count {
+        let tag = s.read::<Tag>()?;
+        let count = s.read::<u16>()?.wrapping_add(1);
+        let references_offset = usize::from(s.read::<u16>()?);
+        if tag == SFNT_TYPE {
+            return Some((type_list.get(references_offset..)?, count));
+        }
+    }
+
+    Some((&[], 0))
+}
@@ -0,0 +1,109 @@
+//! An [Embedded OpenType](https://www.w3.org/submissions/EOT/) (EOT) font parsing.
+//!
+//! Plain and XOR-obfuscated font data is supported.
+//! MicroType Express compressed font data is not.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::parser::{ByteOrder, EndianStream, NumFrom};
+
+const MAGIC: u16 = 0x504C;
+const VERSIONS: &[u32] = &[0x0001_0000, 0x0002_0001, 0x0002_0002];
+
+const FLAG_COMPRESSED: u32 = 0x0000_0004;
+const FLAG_XOR_ENCRYPTED: u32 = 0x1000_0000;
+const XOR_KEY: u8 = 0x50;
+
+/// The fixed part of the header, up to the family name size.
+const FIXED_HEADER_SIZE: usize = 82;
+
+/// Properties from an EOT header.
+#[derive(Clone, Default, Debug)]
+pub struct Header {
+    /// A family name.
+    pub family_name: String,
+    /// A style name, like `Bold Italic`.
+    pub style_name: String,
+    /// A `usWeightClass` copy.
+    pub weight: u16,
+    /// Indicates that the font is italic.
+    pub italic: bool,
+}
+
+/// Checks that the data starts with an EOT header.
+///
+/// Requires only the first 36 bytes.
+pub fn is_eot(data: &[u8]) -> bool {
+    let s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let version = match s.read_u32_at(8) {
+        Some(version) => version,
+        None => return false,
+    };
+
+    VERSIONS.contains(&version) && s.read_u16_at(34) == Some(MAGIC)
+}
+
+/// Parses an EOT header.
+///
+/// Returns `None` when the data is not an EOT font.
+pub fn parse(data: &[u8]) -> Option<Header> {
+    if !is_eot(data) {
+        return None;
+    }
+
+    let mut s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let weight = s.read_u32_at(28)?.min(u32::from(u16::MAX)) as u16;
+
+    // Names are separated by a two-byte padding and prefixed with a byte length.
+    s.skip(FIXED_HEADER_SIZE)?;
+    let mut names = [String::new(), String::new()];
+    for name in &mut names {
+        let len = usize::from(s.read_u16()?);
+        *name = read_utf16(s.read_bytes(len)?);
+        s.skip(2)?;
+    }
+
+    let [family_name, style_name] = names;
+    Some(Header {
+        family_name,
+        style_name,
+        weight,
+        italic: data[27] != 0,
+    })
+}
+
+/// Extracts the font data.
+///
+/// Returns `None` when the data is not an EOT font or the font data is compressed.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    if !is_eot(data) {
+        return None;
+    }
+
+    let s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let eot_size = usize::num_from(s.read_u32_at(0)?);
+    let font_data_size = usize::num_from(s.read_u32_at(4)?);
+    let flags = s.read_u32_at(12)?;
+    if flags & FLAG_COMPRESSED != 0 {
+        return None;
+    }
+
+    // The font data is always the last field.
+    let start = eot_size.checked_sub(font_data_size)?;
+    let mut font_data = data.get(start..eot_size)?.to_vec();
+    if flags & FLAG_XOR_ENCRYPTED != 0 {
+        font_data.iter_mut().for_each(|b| *b ^= XOR_KEY);
+    }
+
+    Some(font_data)
+}
+
+fn read_utf16(data: &[u8]) -> String {
+    let units = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
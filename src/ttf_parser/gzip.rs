@@ -0,0 +1,99 @@
+//! A [gzip](https://www.rfc-editor.org/rfc/rfc1952) compressed font decoding.
+//!
+//! Only a single gzip member is supported, which is what `gzip` produces.
+
+use alloc::vec::Vec;
+
+const MAGIC: &[u8] = &[0x1F, 0x8B];
+const DEFLATE_METHOD: u8 = 8;
+
+const FLAG_HCRC: u8 = 1 << 1;
+const FLAG_EXTRA: u8 = 1 << 2;
+const FLAG_NAME: u8 = 1 << 3;
+const FLAG_COMMENT: u8 = 1 << 4;
+const RESERVED_FLAGS: u8 = 0b1110_0000;
+
+const HEADER_SIZE: usize = 10;
+const TRAILER_SIZE: usize = 8;
+
+/// Checks that the data starts with a gzip header.
+///
+/// Requires only the first 3 bytes.
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(MAGIC) && data.get(2) == Some(&DEFLATE_METHOD)
+}
+
+/// Decompresses gzip data.
+///
+/// Returns `None` when the data is not a valid gzip stream.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    let deflate = deflate_data(data)?;
+
+    // The uncompressed size modulo 2^32.
+    let trailer = data.get(data.len().checked_sub(TRAILER_SIZE)?..)?;
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+
+    let decoded =
+        miniz_oxide::inflate::decompress_to_vec_with_limit(deflate, size as usize).ok()?;
+    if decoded.len() as u32 != size {
+        return None;
+    }
+
+    Some(decoded)
+}
+
+/// Decompresses up to `len` first bytes of gzip data.
+///
+/// Unlike [`decode`], the data can be truncated. Used for content sniffing.
+#[cfg(feature = "fs")]
+pub fn decode_prefix(data: &[u8], len: usize) -> Option<Vec<u8>> {
+    use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+    use miniz_oxide::inflate::TINFLStatus;
+
+    let deflate = deflate_data(data)?;
+
+    let mut decoded = alloc::vec![0; len];
+    let flags = inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF
+        | inflate_flags::TINFL_FLAG_HAS_MORE_INPUT;
+    let mut decompressor = DecompressorOxide::new();
+    let (status, _, written) = decompress(&mut decompressor, deflate, &mut decoded, 0, flags);
+    match status {
+        TINFLStatus::Done | TINFLStatus::HasMoreOutput | TINFLStatus::NeedsMoreInput => {}
+        _ => return None,
+    }
+
+    decoded.truncate(written);
+    Some(decoded)
+}
+
+/// Returns the deflate stream, including the trailer, by skipping the header.
+fn deflate_data(data: &[u8]) -> Option<&[u8]> {
+    if !is_gzip(data) {
+        return None;
+    }
+
+    let flags = *data.get(3)?;
+    if flags & RESERVED_FLAGS != 0 {
+        return None;
+    }
+
+    // Magic, method, flags, modification time, extra flags and OS.
+    let mut offset = HEADER_SIZE;
+    if flags & FLAG_EXTRA != 0 {
+        let len = data.get(offset..offset + 2)?;
+        offset += 2 + usize::from(u16::from_le_bytes([len[0], len[1]]));
+    }
+
+    // Zero-terminated original file name and comment.
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            offset += data.get(offset..)?.iter().position(|b| *b == 0)? + 1;
+        }
+    }
+
+    if flags & FLAG_HCRC != 0 {
+        offset += 2;
+    }
+
+    data.get(offset..)
+}
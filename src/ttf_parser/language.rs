@@ -216,6 +216,7 @@ static TABLE: &[(u16, Language, &str, &str)] = &[
 #[allow(missing_docs)]
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Language {
     Unknown = 0,
     Afrikaans_SouthAfrica,
@@ -434,6 +435,12 @@ impl Language {
         }
     }
 
+    /// Returns a Windows language ID.
+    #[cfg(feature = "fs")]
+    pub(crate) fn windows_id(&self) -> u16 {
+        TABLE[*self as usize].0
+    }
+
     /// Returns the primary language.
     pub fn primary_language(&self) -> &'static str {
         TABLE[*self as usize].2
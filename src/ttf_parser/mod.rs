@@ -4,12 +4,33 @@ inlined from ttf-parser 0.25.1 (MIT / Apache-2.0).
 
 Contains just enough of the original crate to parse font table records,
 the `name` table and parts of the `OS/2` table.
+
+Also includes decoders for font containers that are not supported by ttf-parser,
+like WOFF, WOFF2, gzip and EOT, and Mac resource fork suitcases (`.dfont`) parsing.
+And metadata parsing of non-sfnt fonts, like PostScript Type 1, X11 and Windows bitmap fonts.
 */
 
+pub mod dfont;
+#[cfg(feature = "eot")]
+pub mod eot;
+#[cfg(feature = "gzip")]
+pub mod gzip;
 mod language;
 pub mod name;
 pub mod os2;
 mod parser;
+#[cfg(any(feature = "woff", feature = "woff2"))]
+mod sfnt;
+#[cfg(feature = "type1")]
+pub mod type1;
+#[cfg(feature = "bitmap")]
+pub mod winfnt;
+#[cfg(feature = "woff")]
+pub mod woff;
+#[cfg(feature = "woff2")]
+pub mod woff2;
+#[cfg(feature = "bitmap")]
+pub mod x11;
 
 pub use language::Language;
 pub use name::{name_id, PlatformId};
@@ -125,6 +146,23 @@ impl<'a> RawFace<'a> {
     /// Use [`fonts_in_collection`] to get the total number of font faces.
     /// Set to 0 if unsure.
     pub fn parse(data: &'a [u8], index: u32) -> Result<Self, FaceParsingError> {
+        // Each `sfnt` resource of a suitcase is a separate font, with offsets relative to it.
+        // Resources are parsed as plain fonts, so nested suitcases are rejected.
+        if dfont::is_dfont(data) {
+            let data =
+                dfont::sfnt_resource(data, index).ok_or(FaceParsingError::FaceIndexOutOfBounds)?;
+            if dfont::is_dfont(data) {
+                return Err(FaceParsingError::UnknownMagic);
+            }
+
+            return Self::parse_sfnt(data, 0);
+        }
+
+        Self::parse_sfnt(data, index)
+    }
+
+    /// Parses a TrueType/OpenType font or a font collection.
+    fn parse_sfnt(data: &'a [u8], index: u32) -> Result<Self, FaceParsingError> {
         // https://docs.microsoft.com/en-us/typography/opentype/spec/otff#organization-of-an-opentype-font
 
         let mut s = Stream::new(data);
@@ -195,11 +233,31 @@ impl core::fmt::Debug for RawFace<'_> {
     }
 }
 
-/// Returns the number of fonts stored in a TrueType font collection.
+/// Checks that the data starts with a TrueType/OpenType font or font collection magic.
+#[cfg(any(feature = "fs", feature = "eot"))]
+#[inline]
+pub fn is_sfnt(data: &[u8]) -> bool {
+    Stream::new(data).read::<Magic>().is_some()
+}
+
+/// Returns the number of fonts stored in a TrueType font collection
+/// or a Mac resource fork suitcase.
 ///
-/// Returns `None` if a provided data is not a TrueType font collection.
+/// Returns `None` if a provided data is not a TrueType font collection, a suitcase
+/// or a Windows font library.
 #[inline]
 pub fn fonts_in_collection(data: &[u8]) -> Option<u32> {
+    if dfont::is_dfont(data) {
+        return dfont::fonts_in_dfont(data);
+    }
+
+    #[cfg(feature = "bitmap")]
+    {
+        if winfnt::is_fon(data) {
+            return winfnt::fonts_in_fon(data);
+        }
+    }
+
     let mut s = Stream::new(data);
     if s.read::<Magic>()? != Magic::FontCollection {
         return None;
@@ -70,6 +70,7 @@ impl Default for Weight {
 /// A face [width](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#uswidthclass).
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Width {
     UltraCondensed,
     ExtraCondensed,
@@ -98,6 +99,25 @@ impl Width {
             Width::UltraExpanded => 9,
         }
     }
+
+    /// Creates a width from a numeric representation.
+    ///
+    /// Unknown values are treated as `Normal`.
+    #[inline]
+    pub(crate) fn from_number(n: u16) -> Self {
+        match n {
+            1 => Width::UltraCondensed,
+            2 => Width::ExtraCondensed,
+            3 => Width::Condensed,
+            4 => Width::SemiCondensed,
+            5 => Width::Normal,
+            6 => Width::SemiExpanded,
+            7 => Width::Expanded,
+            8 => Width::ExtraExpanded,
+            9 => Width::UltraExpanded,
+            _ => Width::Normal,
+        }
+    }
 }
 
 impl Default for Width {
@@ -180,18 +200,7 @@ impl<'a> Table<'a> {
     /// Returns face width.
     #[inline]
     pub fn width(&self) -> Width {
-        match Stream::read_at::<u16>(self.data, WIDTH_CLASS_OFFSET).unwrap_or(0) {
-            1 => Width::UltraCondensed,
-            2 => Width::ExtraCondensed,
-            3 => Width::Condensed,
-            4 => Width::SemiCondensed,
-            5 => Width::Normal,
-            6 => Width::SemiExpanded,
-            7 => Width::Expanded,
-            8 => Width::ExtraExpanded,
-            9 => Width::UltraExpanded,
-            _ => Width::Normal,
-        }
+        Width::from_number(Stream::read_at::<u16>(self.data, WIDTH_CLASS_OFFSET).unwrap_or(0))
     }
 
     #[inline]
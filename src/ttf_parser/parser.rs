@@ -15,6 +15,24 @@ pub trait FromData: Sized {
     fn parse(data: &[u8]) -> Option<Self>;
 }
 
+impl FromData for u8 {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        data.first().copied()
+    }
+}
+
+impl FromData for i16 {
+    const SIZE: usize = 2;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        data.try_into().ok().map(i16::from_be_bytes)
+    }
+}
+
 impl FromData for u16 {
     const SIZE: usize = 2;
 
@@ -310,6 +328,116 @@ impl<'a> Stream<'a> {
     }
 }
 
+/// A byte order used by [`EndianStream`].
+#[cfg(any(feature = "bitmap", feature = "eot"))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ByteOrder {
+    // Only PCF fonts can be big-endian.
+    #[cfg_attr(not(feature = "bitmap"), allow(dead_code))]
+    BigEndian,
+    LittleEndian,
+}
+
+/// A streaming binary parser with a selectable byte order.
+///
+/// Used by non-sfnt formats, like PCF, FNT and EOT, which are not always big-endian.
+/// Unlike [`Stream`], all offsets and lengths are checked, even when skipping,
+/// since they usually come from the parsed data itself.
+#[cfg(any(feature = "bitmap", feature = "eot"))]
+#[derive(Clone, Copy, Debug)]
+pub struct EndianStream<'a> {
+    data: &'a [u8],
+    offset: usize,
+    order: ByteOrder,
+}
+
+#[cfg(any(feature = "bitmap", feature = "eot"))]
+impl<'a> EndianStream<'a> {
+    /// Creates a new `EndianStream` parser.
+    #[inline]
+    pub fn new(data: &'a [u8], order: ByteOrder) -> Self {
+        EndianStream {
+            data,
+            offset: 0,
+            order,
+        }
+    }
+
+    /// Advances by the specified `len`.
+    ///
+    /// Returns `None` when there is not enough data left in the stream.
+    #[inline]
+    pub fn skip(&mut self, len: usize) -> Option<()> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    /// Returns the trailing data.
+    ///
+    /// Returns `None` when the offset is past the end of the data.
+    #[cfg(feature = "bitmap")]
+    #[inline]
+    pub fn tail(&self) -> Option<&'a [u8]> {
+        self.data.get(self.offset..)
+    }
+
+    /// Reads N bytes from the stream.
+    #[inline]
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let v = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(v)
+    }
+
+    /// Reads a `u8` from the stream.
+    #[cfg(feature = "bitmap")]
+    #[inline]
+    pub fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+
+    /// Reads a `u16` from the stream.
+    #[inline]
+    pub fn read_u16(&mut self) -> Option<u16> {
+        let b = self.read_bytes(2)?;
+        let b = [b[0], b[1]];
+        Some(match self.order {
+            ByteOrder::BigEndian => u16::from_be_bytes(b),
+            ByteOrder::LittleEndian => u16::from_le_bytes(b),
+        })
+    }
+
+    /// Reads a `u32` from the stream.
+    #[inline]
+    pub fn read_u32(&mut self) -> Option<u32> {
+        let b = self.read_bytes(4)?;
+        let b = [b[0], b[1], b[2], b[3]];
+        Some(match self.order {
+            ByteOrder::BigEndian => u32::from_be_bytes(b),
+            ByteOrder::LittleEndian => u32::from_le_bytes(b),
+        })
+    }
+
+    /// Reads a `u16` at the offset from the start of the stream data.
+    ///
+    /// Doesn't advance the stream.
+    #[inline]
+    pub fn read_u16_at(&self, offset: usize) -> Option<u16> {
+        let mut s = *self;
+        s.offset = offset;
+        s.read_u16()
+    }
+
+    /// Reads a `u32` at the offset from the start of the stream data.
+    ///
+    /// Doesn't advance the stream.
+    #[inline]
+    pub fn read_u32_at(&self, offset: usize) -> Option<u32> {
+        let mut s = *self;
+        s.offset = offset;
+        s.read_u32()
+    }
+}
+
 /// A common offset methods.
 pub trait Offset {
     /// Converts the offset to `usize`.
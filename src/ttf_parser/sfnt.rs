@@ -0,0 +1,123 @@
+//! An sfnt font writer.
+//!
+//! Used to reconstruct a raw TrueType/OpenType font from a font container.
+
+use alloc::vec::Vec;
+
+use super::Tag;
+
+/// Builds an sfnt font from a list of tables.
+///
+/// Table records will be sorted by tag and tables will be padded to a 4-byte boundary.
+/// Checksums are recalculated, except the `head` table `checkSumAdjustment`,
+/// which is not used by anyone anyway.
+pub fn build(flavor: u32, tables: &[(Tag, Vec<u8>)], capacity: usize) -> Vec<u8> {
+    let indices: Vec<usize> = (0..tables.len()).collect();
+    let offsets = table_offsets(12 + tables.len() * 16, tables);
+
+    let mut data = Vec::with_capacity(capacity);
+    write_offset_table(flavor, &indices, tables, &offsets, &mut data);
+    write_tables(tables, &mut data);
+    data
+}
+
+/// Builds a font collection from a list of tables.
+///
+/// Each font is represented by a flavor and a list of indices in `tables`.
+/// Tables can be shared between fonts.
+#[cfg(feature = "woff2")]
+pub fn build_collection(
+    fonts: &[(u32, Vec<usize>)],
+    tables: &[(Tag, Vec<u8>)],
+    capacity: usize,
+) -> Vec<u8> {
+    const COLLECTION_MAGIC: u32 = 0x74746366;
+
+    let header_size = 12 + fonts.len() * 4;
+    let offset_tables_size: usize = fonts.iter().map(|(_, t)| 12 + t.len() * 16).sum();
+    let offsets = table_offsets(header_size + offset_tables_size, tables);
+
+    let mut data = Vec::with_capacity(capacity);
+    data.extend_from_slice(&COLLECTION_MAGIC.to_be_bytes());
+    data.extend_from_slice(&0x00010000u32.to_be_bytes()); // version
+    data.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+
+    let mut offset = header_size;
+    for (_, indices) in fonts {
+        data.extend_from_slice(&(offset as u32).to_be_bytes());
+        offset += 12 + indices.len() * 16;
+    }
+
+    for (flavor, indices) in fonts {
+        write_offset_table(*flavor, indices, tables, &offsets, &mut data);
+    }
+
+    write_tables(tables, &mut data);
+    data
+}
+
+fn table_offsets(mut offset: usize, tables: &[(Tag, Vec<u8>)]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(tables.len());
+    for (_, table) in tables {
+        offsets.push(offset);
+        offset += padded_len(table.len());
+    }
+
+    offsets
+}
+
+fn write_offset_table(
+    flavor: u32,
+    indices: &[usize],
+    tables: &[(Tag, Vec<u8>)],
+    offsets: &[usize],
+    data: &mut Vec<u8>,
+) {
+    let mut indices = indices.to_vec();
+    indices.sort_by_key(|&i| tables[i].0);
+
+    let num_tables = indices.len() as u16;
+    let mut entry_selector = 0u16;
+    while (1u32 << (entry_selector + 1)) <= u32::from(num_tables) {
+        entry_selector += 1;
+    }
+    let search_range = (1u16 << entry_selector).wrapping_mul(16);
+    let range_shift = num_tables.wrapping_mul(16).wrapping_sub(search_range);
+
+    data.extend_from_slice(&flavor.to_be_bytes());
+    data.extend_from_slice(&num_tables.to_be_bytes());
+    data.extend_from_slice(&search_range.to_be_bytes());
+    data.extend_from_slice(&entry_selector.to_be_bytes());
+    data.extend_from_slice(&range_shift.to_be_bytes());
+
+    for i in indices {
+        let (tag, ref table) = tables[i];
+        data.extend_from_slice(&tag.0.to_be_bytes());
+        data.extend_from_slice(&checksum(table).to_be_bytes());
+        data.extend_from_slice(&(offsets[i] as u32).to_be_bytes());
+        data.extend_from_slice(&(table.len() as u32).to_be_bytes());
+    }
+}
+
+fn write_tables(tables: &[(Tag, Vec<u8>)], data: &mut Vec<u8>) {
+    for (_, table) in tables {
+        data.extend_from_slice(table);
+        data.resize(data.len() + padded_len(table.len()) - table.len(), 0);
+    }
+}
+
+#[inline]
+fn padded_len(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    let mut sum = 0u32;
+    for chunk in data.chunks(4) {
+        let mut buf = [0u8; 4];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        sum = sum.wrapping_add(u32::from_be_bytes(buf));
+    }
+
+    sum
+}
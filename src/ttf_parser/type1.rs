@@ -0,0 +1,177 @@
+//! A [PostScript Type 1](https://adobe-type-tools.github.io/font-tech-notes/pdfs/T1_SPEC.pdf)
+//! font metadata parsing.
+//!
+//! Both binary (PFB) and ASCII (PFA) fonts are supported.
+//! Only the cleartext part of the font program is parsed, the encrypted one is ignored.
+
+use alloc::string::String;
+
+const PFB_MARKER: u8 = 0x80;
+const PFB_ASCII_SEGMENT: u8 = 1;
+
+/// Properties from a Type 1 font dictionary.
+#[derive(Clone, Default, Debug)]
+pub struct FontInfo {
+    /// `/FontName`, which is a PostScript name.
+    pub font_name: String,
+    /// `/FamilyName` from the `FontInfo` dictionary.
+    pub family_name: Option<String>,
+    /// `/FullName` from the `FontInfo` dictionary.
+    pub full_name: Option<String>,
+    /// `/Weight` from the `FontInfo` dictionary, like `Bold`.
+    pub weight: Option<String>,
+    /// `/ItalicAngle` from the `FontInfo` dictionary.
+    pub italic_angle: f32,
+    /// `/isFixedPitch` from the `FontInfo` dictionary.
+    pub is_fixed_pitch: bool,
+}
+
+/// Checks that the data starts with a PFB or PFA font header.
+///
+/// Requires only the first 30 bytes.
+pub fn is_type1(data: &[u8]) -> bool {
+    is_pfa(pfb_ascii_segment(data).unwrap_or(data))
+}
+
+fn is_pfa(data: &[u8]) -> bool {
+    data.starts_with(b"%!PS-AdobeFont") || data.starts_with(b"%!FontType1")
+}
+
+/// Returns the first PFB segment data, which is always an ASCII one.
+///
+/// The returned data can be truncated when the input data is.
+fn pfb_ascii_segment(data: &[u8]) -> Option<&[u8]> {
+    if data.len() < 6 || data[0] != PFB_MARKER || data[1] != PFB_ASCII_SEGMENT {
+        return None;
+    }
+
+    let len = u32::from_le_bytes([data[2], data[3], data[4], data[5]]) as usize;
+    let end = data.len().min(len.saturating_add(6));
+    Some(&data[6..end])
+}
+
+/// Parses the font dictionary.
+///
+/// Returns `None` when the data is not a Type 1 font or `/FontName` is missing.
+pub fn parse(data: &[u8]) -> Option<FontInfo> {
+    let text = pfb_ascii_segment(data).unwrap_or(data);
+    if !is_pfa(text) {
+        return None;
+    }
+
+    // Everything after `eexec` is encrypted.
+    let text = match find(text, b"eexec") {
+        Some(end) => &text[..end],
+        None => text,
+    };
+
+    let mut info = FontInfo::default();
+    let mut s = text;
+    while let Some(pos) = s.iter().position(|c| *c == b'/') {
+        s = &s[pos + 1..];
+        let key = read_name(s);
+        s = &s[key.len()..];
+        // Values in the `FontInfo` dictionary are never overridden.
+        match key {
+            b"FontName" if info.font_name.is_empty() => {
+                let value = skip_spaces(s);
+                if value.first() == Some(&b'/') {
+                    info.font_name = String::from_utf8_lossy(read_name(&value[1..])).into_owned();
+                }
+            }
+            b"FamilyName" if info.family_name.is_none() => info.family_name = read_string(s),
+            b"FullName" if info.full_name.is_none() => info.full_name = read_string(s),
+            b"Weight" if info.weight.is_none() => info.weight = read_string(s),
+            b"ItalicAngle" => info.italic_angle = read_number(s).unwrap_or(0.0),
+            b"isFixedPitch" => info.is_fixed_pitch = skip_spaces(s).starts_with(b"true"),
+            _ => {}
+        }
+    }
+
+    if info.font_name.is_empty() {
+        return None;
+    }
+
+    Some(info)
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+fn skip_spaces(data: &[u8]) -> &[u8] {
+    let start = data
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    &data[start..]
+}
+
+/// Reads a name until a whitespace or a PostScript delimiter.
+fn read_name(data: &[u8]) -> &[u8] {
+    let end = data
+        .iter()
+        .position(|c| c.is_ascii_whitespace() || b"()<>[]{}/%".contains(c))
+        .unwrap_or(data.len());
+    &data[..end]
+}
+
+fn read_number(data: &[u8]) -> Option<f32> {
+    let data = skip_spaces(data);
+    let end = data
+        .iter()
+        .position(|c| !(c.is_ascii_digit() || b"+-.".contains(c)))
+        .unwrap_or(data.len());
+    core::str::from_utf8(&data[..end]).ok()?.parse().ok()
+}
+
+/// Reads a `(string)`, handling nested parentheses and escapes.
+fn read_string(data: &[u8]) -> Option<String> {
+    let data = skip_spaces(data);
+    if data.first() != Some(&b'(') {
+        return None;
+    }
+
+    let mut bytes = alloc::vec::Vec::new();
+    let mut depth = 0;
+    let mut iter = data[1..].iter().peekable();
+    while let Some(c) = iter.next() {
+        match *c {
+            b'\\' => match *iter.next()? {
+                b'n' => bytes.push(b'\n'),
+                b't' => bytes.push(b'\t'),
+                // Up to 3 octal digits.
+                c @ b'0'..=b'7' => {
+                    let mut code = u32::from(c - b'0');
+                    for _ in 0..2 {
+                        match iter.peek() {
+                            Some(d @ b'0'..=b'7') => {
+                                code = code * 8 + u32::from(**d - b'0');
+                                iter.next();
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    bytes.push(code as u8);
+                }
+                c => bytes.push(c),
+            },
+            b'(' => {
+                depth += 1;
+                bytes.push(b'(');
+            }
+            b')' if depth == 0 => {
+                // Names are usually ASCII, otherwise Latin-1 is the most likely encoding.
+                return Some(bytes.iter().map(|b| char::from(*b)).collect());
+            }
+            b')' => {
+                depth -= 1;
+                bytes.push(b')');
+            }
+            c => bytes.push(c),
+        }
+    }
+
+    None
+}
@@ -0,0 +1,189 @@
+//! Windows bitmap fonts (`.fnt`) and [NE](https://learn.microsoft.com/en-us/windows/win32/menurc/resource-file-formats)
+//! font libraries (`.fon`) parsing.
+//!
+//! Each `FONT` resource of a font library is a separate font.
+//! Only the FNT header is parsed, glyphs are not.
+
+use alloc::string::String;
+
+use super::parser::{ByteOrder, EndianStream, NumFrom};
+
+const MZ_MAGIC: &[u8] = b"MZ";
+const NE_MAGIC: &[u8] = b"NE";
+/// `RT_FONT` with the integer ID flag.
+const FONT_RESOURCE: u16 = 0x8008;
+/// The FNT header size up to and including `dfFace`.
+const FNT_HEADER_SIZE: usize = 109;
+/// The NE header size up to and including the resource table offset.
+#[cfg(feature = "fs")]
+const NE_HEADER_SIZE: usize = 0x26;
+
+/// Properties from an FNT header.
+#[derive(Clone, Default, Debug)]
+pub struct FontInfo {
+    /// `dfFace`, which is a face name.
+    pub face_name: String,
+    /// `dfWeight`.
+    pub weight: u16,
+    /// `dfItalic`.
+    pub italic: bool,
+    /// Indicates that `dfPitchAndFamily` is a fixed pitch.
+    pub is_fixed_pitch: bool,
+    /// `dfPixHeight`.
+    pub pixel_height: u16,
+    /// Indicates that `dfType` is a vector font.
+    pub is_vector: bool,
+}
+
+/// Checks that the data is an NE executable, which font libraries are.
+///
+/// Unlike other checks, requires the data up to the resource table.
+pub fn is_fon(data: &[u8]) -> bool {
+    resource_table(data).is_some()
+}
+
+/// Checks that the data starts with an FNT header.
+pub fn is_fnt(data: &[u8]) -> bool {
+    let mut s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let version = s.read_u16();
+    let size = s.read_u32();
+    match (version, size) {
+        (Some(version), Some(size)) => {
+            (version == 0x0200 || version == 0x0300)
+                && (FNT_HEADER_SIZE..=data.len()).contains(&usize::num_from(size))
+        }
+        _ => false,
+    }
+}
+
+/// Returns the number of bytes required by [`is_fon`] and [`is_fnt`].
+///
+/// Unlike other formats, both checks depend on offsets and sizes stored in the data,
+/// so the required length can grow once more data is available.
+///
+/// Returns `None` when the data is neither an executable nor an FNT font.
+#[cfg(feature = "fs")]
+pub fn signature_len(data: &[u8]) -> Option<usize> {
+    let mut s = EndianStream::new(data, ByteOrder::LittleEndian);
+    if data.starts_with(MZ_MAGIC) {
+        let ne_offset = usize::num_from(s.read_u32_at(0x3C)?);
+        let ne_header_end = ne_offset.checked_add(NE_HEADER_SIZE)?;
+        // The resource table offset is stored in the NE header.
+        return match data.get(ne_offset..ne_header_end) {
+            Some(ne_header) => {
+                let ne_header = EndianStream::new(ne_header, ByteOrder::LittleEndian);
+                let table_offset = usize::from(ne_header.read_u16_at(0x24)?);
+                Some(ne_offset.checked_add(table_offset)?.max(ne_header_end))
+            }
+            None => Some(ne_header_end),
+        };
+    }
+
+    let version = s.read_u16()?;
+    if version == 0x0200 || version == 0x0300 {
+        s.read_u32().map(usize::num_from)
+    } else {
+        None
+    }
+}
+
+/// Returns the number of `FONT` resources.
+pub fn fonts_in_fon(data: &[u8]) -> Option<u32> {
+    let (_, _, count) = font_resources(data)?;
+    Some(u32::from(count))
+}
+
+/// Returns the data of a `FONT` resource by index.
+pub fn font_resource(data: &[u8], index: u32) -> Option<&[u8]> {
+    let (resources, shift, count) = font_resources(data)?;
+    if index >= u32::from(count) {
+        return None;
+    }
+
+    // Each resource is: offset (u16), length (u16), flags (u16), ID (u16)
+    // and a reserved handle and usage (u32). Offset and length are shifted.
+    let mut s = EndianStream::new(resources, ByteOrder::LittleEndian);
+    s.skip(usize::num_from(index) * 12)?;
+    let offset = usize::from(s.read_u16()?).checked_shl(shift)?;
+    let length = usize::from(s.read_u16()?).checked_shl(shift)?;
+    // The last resource padding can be missing.
+    let end = offset.checked_add(length)?.min(data.len());
+    let resource = data.get(offset..end)?;
+
+    // The resource length is aligned, so use the actual font size.
+    let size = EndianStream::new(resource, ByteOrder::LittleEndian).read_u32_at(2)?;
+    let size = usize::num_from(size);
+    resource.get(..size)
+}
+
+/// Parses an FNT header.
+///
+/// Returns `None` when the data is not an FNT font.
+pub fn parse_fnt(data: &[u8]) -> Option<FontInfo> {
+    if !is_fnt(data) {
+        return None;
+    }
+
+    let s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let font_type = s.read_u16_at(66)?;
+    let italic = *data.get(80)? != 0;
+    let weight = s.read_u16_at(83)?;
+    let pixel_height = s.read_u16_at(88)?;
+    let pitch_and_family = *data.get(90)?;
+    let face_offset = usize::num_from(s.read_u32_at(105)?);
+
+    let name = data.get(face_offset..)?;
+    let end = name.iter().position(|b| *b == 0)?;
+    // Face names are ANSI, which is usually Windows-1252, so treat them as Latin-1.
+    let face_name = name[..end].iter().map(|b| char::from(*b)).collect();
+
+    Some(FontInfo {
+        face_name,
+        weight,
+        italic,
+        // The lowest bit is set for variable pitch fonts.
+        is_fixed_pitch: pitch_and_family & 1 == 0,
+        pixel_height,
+        is_vector: font_type & 1 != 0,
+    })
+}
+
+/// Returns the NE resource table.
+fn resource_table(data: &[u8]) -> Option<&[u8]> {
+    if !data.starts_with(MZ_MAGIC) {
+        return None;
+    }
+
+    let s = EndianStream::new(data, ByteOrder::LittleEndian);
+    let ne_offset = usize::num_from(s.read_u32_at(0x3C)?);
+    let ne_header = data.get(ne_offset..)?;
+    if !ne_header.starts_with(NE_MAGIC) {
+        return None;
+    }
+
+    let s = EndianStream::new(ne_header, ByteOrder::LittleEndian);
+    let table_offset = usize::from(s.read_u16_at(0x24)?);
+    ne_header.get(table_offset..)
+}
+
+/// Returns the `FONT` resources list, the alignment shift and the number of resources in it.
+fn font_resources(data: &[u8]) -> Option<(&[u8], u32, u16)> {
+    let table = resource_table(data)?;
+    let mut s = EndianStream::new(table, ByteOrder::LittleEndian);
+    let shift = u32::from(s.read_u16()?);
+    loop {
+        // A zero type ID terminates the types list.
+        let type_id = s.read_u16()?;
+        if type_id == 0 {
+            return Some((&[], shift, 0));
+        }
+
+        let count = s.read_u16()?;
+        s.skip(4)?; // reserved
+        if type_id == FONT_RESOURCE {
+            return Some((s.tail()?, shift, count));
+        }
+
+        s.skip(usize::from(count) * 12)?;
+    }
+}
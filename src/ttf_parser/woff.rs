@@ -0,0 +1,89 @@
+//! A [WOFF 1.0](https://www.w3.org/TR/WOFF/) font container decoder.
+
+use alloc::vec::Vec;
+
+use super::parser::{FromData, NumFrom, Stream};
+use super::Tag;
+
+/// A `wOFF` signature.
+pub const SIGNATURE: u32 = 0x774F4646;
+
+const HEADER_SIZE: usize = 44;
+
+#[derive(Clone, Copy)]
+struct TableDirectoryEntry {
+    tag: Tag,
+    offset: u32,
+    comp_length: u32,
+    orig_length: u32,
+}
+
+impl FromData for TableDirectoryEntry {
+    const SIZE: usize = 20;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(TableDirectoryEntry {
+            tag: s.read::<Tag>()?,
+            offset: s.read::<u32>()?,
+            comp_length: s.read::<u32>()?,
+            orig_length: s.read::<u32>()?,
+            // origChecksum will be recalculated anyway.
+        })
+    }
+}
+
+/// Checks that the data starts with a WOFF signature.
+#[inline]
+pub fn is_woff(data: &[u8]) -> bool {
+    Stream::read_at::<u32>(data, 0) == Some(SIGNATURE)
+}
+
+/// Decodes a WOFF font into a raw sfnt data.
+///
+/// Returns `None` when the data is not a valid WOFF font.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    if s.read::<u32>()? != SIGNATURE {
+        return None;
+    }
+
+    let flavor = s.read::<u32>()?;
+    s.skip::<u32>(); // length
+    let num_tables = s.read::<u16>()?;
+    s.skip::<u16>(); // reserved
+    let total_sfnt_size = s.read::<u32>()?;
+    s.advance(HEADER_SIZE - s.offset()); // version + metadata and private blocks
+
+    let entries = s.read_array16::<TableDirectoryEntry>(num_tables)?;
+
+    let mut tables = Vec::with_capacity(usize::from(num_tables));
+    for entry in entries {
+        let start = usize::num_from(entry.offset);
+        let end = start.checked_add(usize::num_from(entry.comp_length))?;
+        let table_data = data.get(start..end)?;
+
+        let orig_length = usize::num_from(entry.orig_length);
+        let table = if entry.comp_length == entry.orig_length {
+            table_data.to_vec()
+        } else if entry.comp_length < entry.orig_length {
+            let table =
+                miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(table_data, orig_length)
+                    .ok()?;
+            if table.len() != orig_length {
+                return None;
+            }
+
+            table
+        } else {
+            return None;
+        };
+
+        tables.push((entry.tag, table));
+    }
+
+    // Not a hard limit, just a preallocation hint.
+    let capacity = usize::num_from(total_sfnt_size).min(data.len().saturating_mul(4));
+    Some(super::sfnt::build(flavor, &tables, capacity))
+}
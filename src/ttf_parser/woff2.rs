@@ -0,0 +1,684 @@
+//! A [WOFF 2.0](https://www.w3.org/TR/WOFF2/) font container decoder.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use super::parser::{NumFrom, Stream};
+use super::Tag;
+
+/// A `wOF2` signature.
+pub const SIGNATURE: u32 = 0x774F4632;
+
+const COLLECTION_FLAVOR: u32 = 0x74746366;
+const HEADER_SIZE: usize = 48;
+/// A decompressed tables stream size limit, to reject malformed fonts early.
+const MAX_STREAM_SIZE: usize = 256 * 1024 * 1024;
+
+const GLYF: Tag = Tag::from_bytes(b"glyf");
+const LOCA: Tag = Tag::from_bytes(b"loca");
+const HMTX: Tag = Tag::from_bytes(b"hmtx");
+const HEAD: Tag = Tag::from_bytes(b"head");
+const HHEA: Tag = Tag::from_bytes(b"hhea");
+const MAXP: Tag = Tag::from_bytes(b"maxp");
+
+// https://www.w3.org/TR/WOFF2/#table_dir_format
+#[rustfmt::skip]
+const KNOWN_TAGS: &[&[u8; 4]; 63] = &[
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+    b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+    b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+    b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+    b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+#[derive(Clone, Copy)]
+struct TableDirectoryEntry {
+    tag: Tag,
+    transformed: bool,
+    orig_length: u32,
+    /// An offset in the decompressed stream.
+    offset: usize,
+    /// A length in the decompressed stream.
+    length: usize,
+}
+
+/// Checks that the data starts with a WOFF2 signature.
+#[inline]
+pub fn is_woff2(data: &[u8]) -> bool {
+    Stream::read_at::<u32>(data, 0) == Some(SIGNATURE)
+}
+
+/// Decodes a WOFF2 font into a raw sfnt data.
+///
+/// Font collections will be decoded into a TrueType collection.
+///
+/// Returns `None` when the data is not a valid WOFF2 font.
+pub fn decode(data: &[u8]) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    if s.read::<u32>()? != SIGNATURE {
+        return None;
+    }
+
+    let flavor = s.read::<u32>()?;
+    s.skip::<u32>(); // length
+    let num_tables = s.read::<u16>()?;
+    s.skip::<u16>(); // reserved
+    let total_sfnt_size = s.read::<u32>()?;
+    let total_compressed_size = s.read::<u32>()?;
+    s.advance(HEADER_SIZE - s.offset()); // version + metadata and private blocks
+
+    let mut entries = Vec::with_capacity(usize::from(num_tables));
+    let mut stream_size = 0usize;
+    for _ in 0..num_tables {
+        let flags = s.read::<u8>()?;
+        let tag = match flags & 0x3F {
+            63 => s.read::<Tag>()?,
+            n => Tag::from_bytes(KNOWN_TAGS[usize::from(n)]),
+        };
+
+        // glyf and loca use 3 as the null transform, everything else uses 0.
+        let transform_version = flags >> 6;
+        let transformed = if tag == GLYF || tag == LOCA {
+            transform_version != 3
+        } else {
+            transform_version != 0
+        };
+
+        let orig_length = read_uint_base128(&mut s)?;
+        let length = if transformed {
+            read_uint_base128(&mut s)?
+        } else {
+            orig_length
+        };
+
+        let length = usize::num_from(length);
+        entries.push(TableDirectoryEntry {
+            tag,
+            transformed,
+            orig_length,
+            offset: stream_size,
+            length,
+        });
+        stream_size = stream_size.checked_add(length)?;
+    }
+
+    // A list of fonts, where each font is a flavor and a list of table indices.
+    let mut fonts = Vec::new();
+    if flavor == COLLECTION_FLAVOR {
+        s.skip::<u32>(); // version
+        let num_fonts = read_255_u16(&mut s)?;
+        for _ in 0..num_fonts {
+            let num_tables = read_255_u16(&mut s)?;
+            let flavor = s.read::<u32>()?;
+            let mut indices = Vec::with_capacity(usize::from(num_tables));
+            for _ in 0..num_tables {
+                let index = usize::from(read_255_u16(&mut s)?);
+                if index >= entries.len() {
+                    return None;
+                }
+
+                indices.push(index);
+            }
+
+            fonts.push((flavor, indices));
+        }
+    } else {
+        fonts.push((flavor, (0..entries.len()).collect()));
+    }
+
+    let compressed = read_sized(&mut s, total_compressed_size)?;
+    let stream = decompress(compressed, stream_size)?;
+
+    // Table data is stored by entry index, since collections can share tables.
+    let mut tables: Vec<Option<Vec<u8>>> = (0..entries.len()).map(|_| None).collect();
+    for (_, indices) in &fonts {
+        reconstruct_font(&entries, indices, &stream, &mut tables)?;
+    }
+
+    let tables: Vec<(Tag, Vec<u8>)> = entries
+        .iter()
+        .zip(tables)
+        .map(|(entry, table)| table.map(|table| (entry.tag, table)))
+        .collect::<Option<_>>()?;
+
+    // Not a hard limit, just a preallocation hint.
+    let capacity = usize::num_from(total_sfnt_size).min(data.len().saturating_mul(8));
+    if flavor == COLLECTION_FLAVOR {
+        Some(super::sfnt::build_collection(&fonts, &tables, capacity))
+    } else {
+        Some(super::sfnt::build(flavor, &tables, capacity))
+    }
+}
+
+/// Decompresses the tables stream.
+///
+/// The buffer grows with the actual data, so a bogus `size` doesn't cause a huge allocation.
+fn decompress(data: &[u8], size: usize) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    if size > MAX_STREAM_SIZE {
+        return None;
+    }
+
+    let mut stream = Vec::new();
+    brotli_decompressor::Decompressor::new(data, 4096)
+        .take(size as u64)
+        .read_to_end(&mut stream)
+        .ok()?;
+    if stream.len() != size {
+        return None;
+    }
+
+    Some(stream)
+}
+
+/// Reconstructs tables of a single font.
+fn reconstruct_font(
+    entries: &[TableDirectoryEntry],
+    indices: &[usize],
+    stream: &[u8],
+    tables: &mut [Option<Vec<u8>>],
+) -> Option<()> {
+    let find = |tag: Tag| indices.iter().copied().find(|&i| entries[i].tag == tag);
+    let entry_data = |index: usize| {
+        let entry = &entries[index];
+        stream.get(entry.offset..entry.offset.checked_add(entry.length)?)
+    };
+
+    // Glyph x minimums are required for the hmtx transform.
+    let mut x_mins = Vec::new();
+    if let Some(glyf_index) = find(GLYF) {
+        let glyf_entry = &entries[glyf_index];
+        let loca_index = find(LOCA)?;
+        if glyf_entry.transformed != entries[loca_index].transformed {
+            return None;
+        }
+
+        if glyf_entry.transformed && tables[glyf_index].is_none() {
+            let loca_length = usize::num_from(entries[loca_index].orig_length);
+            let (glyf, loca) = reconstruct_glyf(entry_data(glyf_index)?, loca_length)?;
+            tables[glyf_index] = Some(glyf);
+            tables[loca_index] = Some(loca);
+        }
+
+        if find(HMTX).is_some_and(|i| entries[i].transformed) {
+            // indexToLocFormat
+            let long_loca = Stream::read_at::<i16>(entry_data(find(HEAD)?)?, 50)? != 0;
+            let glyf = match tables[glyf_index] {
+                Some(ref glyf) => glyf.as_slice(),
+                None => entry_data(glyf_index)?,
+            };
+            let loca = match tables[loca_index] {
+                Some(ref loca) => loca.as_slice(),
+                None => entry_data(loca_index)?,
+            };
+            x_mins = glyph_x_mins(glyf, loca, long_loca)?;
+        }
+    }
+
+    for &index in indices {
+        if tables[index].is_some() {
+            continue;
+        }
+
+        let entry = &entries[index];
+        let table = if !entry.transformed {
+            entry_data(index)?.to_vec()
+        } else if entry.tag == HMTX {
+            let hhea = entry_data(find(HHEA)?)?;
+            let maxp = entry_data(find(MAXP)?)?;
+            reconstruct_hmtx(entry_data(index)?, hhea, maxp, &x_mins)?
+        } else {
+            // Unknown transform.
+            return None;
+        };
+
+        if table.len() != usize::num_from(entry.orig_length) {
+            return None;
+        }
+
+        tables[index] = Some(table);
+    }
+
+    Some(())
+}
+
+fn glyph_x_mins(glyf: &[u8], loca: &[u8], long: bool) -> Option<Vec<i16>> {
+    let offset_size = if long { 4 } else { 2 };
+    let num_glyphs = (loca.len() / offset_size).checked_sub(1)?;
+    let read_offset = |i: usize| -> Option<usize> {
+        if long {
+            Stream::read_at::<u32>(loca, i * 4).map(usize::num_from)
+        } else {
+            Stream::read_at::<u16>(loca, i * 2).map(|n| usize::from(n) * 2)
+        }
+    };
+
+    let mut x_mins = Vec::with_capacity(num_glyphs);
+    for i in 0..num_glyphs {
+        let start = read_offset(i)?;
+        let end = read_offset(i + 1)?;
+        if end > start {
+            x_mins.push(Stream::read_at::<i16>(glyf, start + 2)?);
+        } else {
+            x_mins.push(0);
+        }
+    }
+
+    Some(x_mins)
+}
+
+// https://www.w3.org/TR/WOFF2/#glyf_table_format
+fn reconstruct_glyf(data: &[u8], loca_length: usize) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // reserved
+    let option_flags = s.read::<u16>()?;
+    let num_glyphs = s.read::<u16>()?;
+    let index_format = s.read::<u16>()?;
+
+    let n_contour_size = s.read::<u32>()?;
+    let n_points_size = s.read::<u32>()?;
+    let flag_size = s.read::<u32>()?;
+    let glyph_size = s.read::<u32>()?;
+    let composite_size = s.read::<u32>()?;
+    let bbox_size = s.read::<u32>()?;
+    let instruction_size = s.read::<u32>()?;
+
+    let mut n_contour_stream = Stream::new(read_sized(&mut s, n_contour_size)?);
+    let mut n_points_stream = Stream::new(read_sized(&mut s, n_points_size)?);
+    let mut flag_stream = Stream::new(read_sized(&mut s, flag_size)?);
+    let mut glyph_stream = Stream::new(read_sized(&mut s, glyph_size)?);
+    let mut composite_stream = Stream::new(read_sized(&mut s, composite_size)?);
+    let bbox_data = read_sized(&mut s, bbox_size)?;
+    let mut instruction_stream = Stream::new(read_sized(&mut s, instruction_size)?);
+
+    let bitmap_size = (usize::from(num_glyphs) + 31) / 32 * 4;
+    let bbox_bitmap = bbox_data.get(..bitmap_size)?;
+    let mut bbox_stream = Stream::new(bbox_data.get(bitmap_size..)?);
+
+    let overlap_bitmap = if option_flags & 1 != 0 {
+        Some(s.read_bytes((usize::from(num_glyphs) + 7) / 8)?)
+    } else {
+        None
+    };
+
+    let has_bit = |bitmap: &[u8], index: u16| -> bool {
+        let byte = bitmap.get(usize::from(index / 8)).copied().unwrap_or(0);
+        byte & (0x80 >> (index % 8)) != 0
+    };
+
+    let mut glyf = Vec::new();
+    let mut offsets = Vec::with_capacity(usize::from(num_glyphs) + 1);
+    let mut points = Vec::new();
+    let mut end_points = Vec::new();
+    for glyph_id in 0..num_glyphs {
+        offsets.push(glyf.len());
+
+        let has_bbox = has_bit(bbox_bitmap, glyph_id);
+        let n_contours = n_contour_stream.read::<i16>()?;
+        if n_contours == 0 {
+            // An empty glyph cannot have a bbox.
+            if has_bbox {
+                return None;
+            }
+
+            continue;
+        }
+
+        if n_contours == -1 {
+            // A composite glyph must have an explicit bbox.
+            if !has_bbox {
+                return None;
+            }
+
+            let (components, have_instructions) = read_composite(&mut composite_stream)?;
+
+            glyf.extend_from_slice(&n_contours.to_be_bytes());
+            glyf.extend_from_slice(bbox_stream.read_bytes(8)?);
+            glyf.extend_from_slice(components);
+
+            if have_instructions {
+                let len = read_255_u16(&mut glyph_stream)?;
+                glyf.extend_from_slice(&len.to_be_bytes());
+                glyf.extend_from_slice(instruction_stream.read_bytes(usize::from(len))?);
+            }
+        } else if n_contours > 0 {
+            end_points.clear();
+            let mut num_points = 0u16;
+            for _ in 0..n_contours {
+                let n = read_255_u16(&mut n_points_stream)?;
+                num_points = num_points.checked_add(n)?;
+                end_points.push(num_points.checked_sub(1)?);
+            }
+
+            points.clear();
+            let (mut x, mut y) = (0i32, 0i32);
+            for _ in 0..num_points {
+                let flag = flag_stream.read::<u8>()?;
+                let (dx, dy) = read_triplet(flag & 0x7F, &mut glyph_stream)?;
+                x = x.checked_add(dx)?;
+                y = y.checked_add(dy)?;
+                points.push((x, y, flag & 0x80 == 0));
+            }
+
+            let instructions_len = read_255_u16(&mut glyph_stream)?;
+            let instructions = instruction_stream.read_bytes(usize::from(instructions_len))?;
+
+            glyf.extend_from_slice(&n_contours.to_be_bytes());
+            if has_bbox {
+                glyf.extend_from_slice(bbox_stream.read_bytes(8)?);
+            } else {
+                let (mut x_min, mut y_min) = (i32::MAX, i32::MAX);
+                let (mut x_max, mut y_max) = (i32::MIN, i32::MIN);
+                for &(x, y, _) in &points {
+                    x_min = x_min.min(x);
+                    y_min = y_min.min(y);
+                    x_max = x_max.max(x);
+                    y_max = y_max.max(y);
+                }
+
+                if points.is_empty() {
+                    x_min = 0;
+                    y_min = 0;
+                    x_max = 0;
+                    y_max = 0;
+                }
+
+                for n in [x_min, y_min, x_max, y_max] {
+                    glyf.extend_from_slice(&i16::try_from(n).ok()?.to_be_bytes());
+                }
+            }
+
+            for n in &end_points {
+                glyf.extend_from_slice(&n.to_be_bytes());
+            }
+
+            glyf.extend_from_slice(&instructions_len.to_be_bytes());
+            glyf.extend_from_slice(instructions);
+
+            let overlap = overlap_bitmap.is_some_and(|bitmap| has_bit(bitmap, glyph_id));
+            write_simple_glyph_points(&points, overlap, &mut glyf)?;
+        } else {
+            return None;
+        }
+
+        // Pad glyphs to a 4-byte boundary.
+        glyf.resize((glyf.len() + 3) & !3, 0);
+    }
+    offsets.push(glyf.len());
+
+    let mut loca = Vec::with_capacity(loca_length);
+    if index_format == 0 {
+        for offset in offsets {
+            let offset = u16::try_from(offset / 2).ok()?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        }
+    } else {
+        for offset in offsets {
+            let offset = u32::try_from(offset).ok()?;
+            loca.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
+
+    if loca.len() != loca_length {
+        return None;
+    }
+
+    Some((glyf, loca))
+}
+
+/// Reads a substream which size is stored in the file.
+///
+/// Unlike `Stream::read_bytes`, the untrusted size is checked against the remaining data first.
+fn read_sized<'a>(s: &mut Stream<'a>, size: u32) -> Option<&'a [u8]> {
+    let data = s.tail()?.get(..usize::num_from(size))?;
+    s.advance(data.len());
+    Some(data)
+}
+
+/// Reads composite glyph components.
+///
+/// Returns raw components data and a flag indicating that the glyph has instructions.
+fn read_composite<'a>(s: &mut Stream<'a>) -> Option<(&'a [u8], bool)> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    let data = s.tail()?;
+    let mut len = 0;
+    let mut have_instructions = false;
+    loop {
+        let flags = Stream::read_at::<u16>(data, len)?;
+        have_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+
+        len += 4; // flags + glyphIndex
+        len += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            len += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            len += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            len += 8;
+        }
+
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+
+    Some((s.read_bytes(len)?, have_instructions))
+}
+
+/// Decodes a point coordinates delta.
+///
+/// https://www.w3.org/TR/WOFF2/#triplet_decoding
+fn read_triplet(flag: u8, s: &mut Stream) -> Option<(i32, i32)> {
+    #[inline]
+    fn with_sign(flag: u8, value: i32) -> i32 {
+        if flag & 1 != 0 {
+            value
+        } else {
+            -value
+        }
+    }
+
+    let flag_n = i32::from(flag);
+    let (dx, dy) = if flag < 10 {
+        let b0 = i32::from(s.read::<u8>()?);
+        (0, with_sign(flag, ((flag_n & 14) << 7) + b0))
+    } else if flag < 20 {
+        let b0 = i32::from(s.read::<u8>()?);
+        (with_sign(flag, (((flag_n - 10) & 14) << 7) + b0), 0)
+    } else if flag < 84 {
+        let b0 = flag_n - 20;
+        let b1 = i32::from(s.read::<u8>()?);
+        (
+            with_sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+            with_sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)),
+        )
+    } else if flag < 120 {
+        let b0 = flag_n - 84;
+        let b1 = i32::from(s.read::<u8>()?);
+        let b2 = i32::from(s.read::<u8>()?);
+        (
+            with_sign(flag, 1 + ((b0 / 12) << 8) + b1),
+            with_sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b2),
+        )
+    } else if flag < 124 {
+        let b1 = i32::from(s.read::<u8>()?);
+        let b2 = i32::from(s.read::<u8>()?);
+        let b3 = i32::from(s.read::<u8>()?);
+        (
+            with_sign(flag, (b1 << 4) + (b2 >> 4)),
+            with_sign(flag >> 1, ((b2 & 0x0F) << 8) + b3),
+        )
+    } else {
+        let b1 = i32::from(s.read::<u8>()?);
+        let b2 = i32::from(s.read::<u8>()?);
+        let b3 = i32::from(s.read::<u8>()?);
+        let b4 = i32::from(s.read::<u8>()?);
+        (
+            with_sign(flag, (b1 << 8) + b2),
+            with_sign(flag >> 1, (b3 << 8) + b4),
+        )
+    };
+
+    Some((dx, dy))
+}
+
+/// Writes simple glyph flags and coordinates in the `glyf` table format.
+///
+/// Returns `None` when a coordinate delta doesn't fit into `i16`.
+fn write_simple_glyph_points(
+    points: &[(i32, i32, bool)],
+    overlap: bool,
+    glyf: &mut Vec<u8>,
+) -> Option<()> {
+    const ON_CURVE_POINT: u8 = 0x01;
+    const X_SHORT_VECTOR: u8 = 0x02;
+    const Y_SHORT_VECTOR: u8 = 0x04;
+    const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+    const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+    const OVERLAP_SIMPLE: u8 = 0x40;
+
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let (mut prev_x, mut prev_y) = (0, 0);
+    for (i, &(x, y, on_curve)) in points.iter().enumerate() {
+        let mut flag = 0;
+        if on_curve {
+            flag |= ON_CURVE_POINT;
+        }
+
+        if i == 0 && overlap {
+            flag |= OVERLAP_SIMPLE;
+        }
+
+        let dx = x - prev_x;
+        if dx == 0 {
+            flag |= X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR;
+        } else if dx > -256 && dx < 256 {
+            flag |= X_SHORT_VECTOR;
+            if dx > 0 {
+                flag |= X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR;
+            }
+            xs.push(dx.unsigned_abs() as u8);
+        } else {
+            xs.extend_from_slice(&i16::try_from(dx).ok()?.to_be_bytes());
+        }
+
+        let dy = y - prev_y;
+        if dy == 0 {
+            flag |= Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR;
+        } else if dy > -256 && dy < 256 {
+            flag |= Y_SHORT_VECTOR;
+            if dy > 0 {
+                flag |= Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR;
+            }
+            ys.push(dy.unsigned_abs() as u8);
+        } else {
+            ys.extend_from_slice(&i16::try_from(dy).ok()?.to_be_bytes());
+        }
+
+        glyf.push(flag);
+        prev_x = x;
+        prev_y = y;
+    }
+
+    glyf.extend_from_slice(&xs);
+    glyf.extend_from_slice(&ys);
+    Some(())
+}
+
+// https://www.w3.org/TR/WOFF2/#hmtx_table_format
+fn reconstruct_hmtx(data: &[u8], hhea: &[u8], maxp: &[u8], x_mins: &[i16]) -> Option<Vec<u8>> {
+    let num_h_metrics = usize::from(Stream::read_at::<u16>(hhea, 34)?);
+    let num_glyphs = usize::from(Stream::read_at::<u16>(maxp, 4)?);
+    if num_h_metrics == 0 || num_h_metrics > num_glyphs || x_mins.len() != num_glyphs {
+        return None;
+    }
+
+    let mut s = Stream::new(data);
+    let flags = s.read::<u8>()?;
+    let has_proportional_lsbs = flags & 1 == 0;
+    let has_monospace_lsbs = flags & 2 == 0;
+
+    let advances = s.read_bytes(num_h_metrics * 2)?;
+    let lsbs = if has_proportional_lsbs {
+        Some(s.read_bytes(num_h_metrics * 2)?)
+    } else {
+        None
+    };
+    let mono_lsbs = if has_monospace_lsbs {
+        Some(s.read_bytes((num_glyphs - num_h_metrics) * 2)?)
+    } else {
+        None
+    };
+
+    let mut hmtx = Vec::with_capacity(num_h_metrics * 4 + (num_glyphs - num_h_metrics) * 2);
+    for (i, x_min) in x_mins[..num_h_metrics].iter().enumerate() {
+        hmtx.extend_from_slice(advances.get(i * 2..i * 2 + 2)?);
+        match lsbs {
+            Some(lsbs) => hmtx.extend_from_slice(lsbs.get(i * 2..i * 2 + 2)?),
+            None => hmtx.extend_from_slice(&x_min.to_be_bytes()),
+        }
+    }
+
+    for (i, x_min) in x_mins[num_h_metrics..].iter().enumerate() {
+        match mono_lsbs {
+            Some(lsbs) => hmtx.extend_from_slice(lsbs.get(i * 2..i * 2 + 2)?),
+            None => hmtx.extend_from_slice(&x_min.to_be_bytes()),
+        }
+    }
+
+    Some(hmtx)
+}
+
+// https://www.w3.org/TR/WOFF2/#DataTypes
+fn read_uint_base128(s: &mut Stream) -> Option<u32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = s.read::<u8>()?;
+        // No leading zeros.
+        if i == 0 && byte == 0x80 {
+            return None;
+        }
+
+        // Check for overflow.
+        if value & 0xFE000000 != 0 {
+            return None;
+        }
+
+        value = (value << 7) | u32::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+// https://www.w3.org/TR/WOFF2/#DataTypes
+fn read_255_u16(s: &mut Stream) -> Option<u16> {
+    const WORD_CODE: u8 = 253;
+    const ONE_MORE_BYTE_CODE2: u8 = 254;
+    const ONE_MORE_BYTE_CODE1: u8 = 255;
+    const LOWEST_U_CODE: u16 = 253;
+
+    match s.read::<u8>()? {
+        WORD_CODE => s.read::<u16>(),
+        ONE_MORE_BYTE_CODE1 => Some(u16::from(s.read::<u8>()?) + LOWEST_U_CODE),
+        ONE_MORE_BYTE_CODE2 => Some(u16::from(s.read::<u8>()?) + LOWEST_U_CODE * 2),
+        code => Some(u16::from(code)),
+    }
+}
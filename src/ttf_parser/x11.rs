@@ -0,0 +1,196 @@
+//! X11 bitmap fonts metadata parsing.
+//!
+//! Supports [PCF](https://fontforge.org/docs/techref/pcf-format.html) properties tables
+//! and [BDF](https://adobe-type-tools.github.io/font-tech-notes/pdfs/5005.BDF_Spec.pdf) headers.
+//! Glyphs are not parsed.
+
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+
+use super::parser::{ByteOrder, EndianStream};
+
+const PCF_MAGIC: &[u8] = b"\x01fcp";
+const PCF_PROPERTIES: u32 = 1 << 0;
+const PCF_BYTE_MASK: u32 = 1 << 2;
+
+/// X11 font properties.
+///
+/// Missing properties are taken from the XLFD font name, when possible.
+#[derive(Clone, Default, Debug)]
+pub struct Properties {
+    /// `FONT`, which is an XLFD font name.
+    pub font: Option<String>,
+    /// `FAMILY_NAME`.
+    pub family_name: Option<String>,
+    /// `WEIGHT_NAME`, like `Bold`.
+    pub weight_name: Option<String>,
+    /// `SLANT`, like `R` or `I`.
+    pub slant: Option<String>,
+    /// `SETWIDTH_NAME`, like `Normal` or `Condensed`.
+    pub setwidth_name: Option<String>,
+    /// `SPACING`, like `P`, `M` or `C`.
+    pub spacing: Option<String>,
+    /// `PIXEL_SIZE`. Never zero.
+    pub pixel_size: Option<u16>,
+}
+
+impl Properties {
+    fn set(&mut self, name: &str, value: &str) {
+        let value = Some(value.to_string());
+        match name {
+            "FONT" => self.font = value,
+            "FAMILY_NAME" => self.family_name = value,
+            "WEIGHT_NAME" => self.weight_name = value,
+            "SLANT" => self.slant = value,
+            "SETWIDTH_NAME" => self.setwidth_name = value,
+            "SPACING" => self.spacing = value,
+            _ => {}
+        }
+    }
+
+    /// Fills missing properties from the XLFD font name.
+    ///
+    /// Like `-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso10646-1`.
+    fn fill_from_xlfd(&mut self) {
+        let font = match self.font {
+            Some(ref font) if font.starts_with('-') => font.clone(),
+            _ => return,
+        };
+
+        let fields: alloc::vec::Vec<&str> = font.split('-').collect();
+        if fields.len() != 15 {
+            return;
+        }
+
+        let field = |i: usize| Some(fields[i].to_string()).filter(|s| !s.is_empty());
+        self.family_name = self.family_name.take().or_else(|| field(2));
+        self.weight_name = self.weight_name.take().or_else(|| field(3));
+        self.slant = self.slant.take().or_else(|| field(4));
+        self.setwidth_name = self.setwidth_name.take().or_else(|| field(5));
+        self.spacing = self.spacing.take().or_else(|| field(11));
+        if self.pixel_size.is_none() {
+            self.pixel_size = fields[7].parse().ok();
+        }
+
+        self.pixel_size = self.pixel_size.filter(|size| *size != 0);
+    }
+}
+
+/// Checks that the data starts with a PCF magic.
+pub fn is_pcf(data: &[u8]) -> bool {
+    data.starts_with(PCF_MAGIC)
+}
+
+/// Checks that the data starts with a BDF header.
+pub fn is_bdf(data: &[u8]) -> bool {
+    data.starts_with(b"STARTFONT ")
+}
+
+/// Parses PCF font properties.
+pub fn parse_pcf(data: &[u8]) -> Option<Properties> {
+    if !is_pcf(data) {
+        return None;
+    }
+
+    let mut s = EndianStream::new(data, ByteOrder::LittleEndian);
+    s.skip(4)?;
+    let tables_count = s.read_u32()?;
+    let mut table_offset = None;
+    for _ in 0..tables_count {
+        let kind = s.read_u32()?;
+        s.skip(8)?; // format + size
+        let offset = s.read_u32()?;
+        if kind == PCF_PROPERTIES {
+            table_offset = Some(offset as usize);
+            break;
+        }
+    }
+
+    let table = data.get(table_offset?..)?;
+    // The format is always stored in the little-endian order.
+    let format = EndianStream::new(table, ByteOrder::LittleEndian).read_u32()?;
+    let order = if format & PCF_BYTE_MASK != 0 {
+        ByteOrder::BigEndian
+    } else {
+        ByteOrder::LittleEndian
+    };
+    let mut s = EndianStream::new(table, order);
+    s.skip(4)?;
+    let count = s.read_u32()? as usize;
+
+    let mut records = alloc::vec::Vec::new();
+    for _ in 0..count {
+        let name_offset = s.read_u32()? as usize;
+        let is_string = s.read_u8()? != 0;
+        let value = s.read_u32()?;
+        records.push((name_offset, is_string, value));
+    }
+
+    if count & 3 != 0 {
+        s.skip(4 - (count & 3))?;
+    }
+
+    let strings_len = s.read_u32()? as usize;
+    let strings = s.read_bytes(strings_len)?;
+    let string_at = |offset: usize| {
+        let data = strings.get(offset..)?;
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        core::str::from_utf8(&data[..end]).ok()
+    };
+
+    let mut props = Properties::default();
+    for (name_offset, is_string, value) in records {
+        let name = match string_at(name_offset) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        if is_string {
+            if let Some(value) = string_at(value as usize) {
+                props.set(name, value);
+            }
+        } else if name == "PIXEL_SIZE" {
+            props.pixel_size = u16::try_from(value as i32).ok();
+        }
+    }
+
+    props.fill_from_xlfd();
+    Some(props)
+}
+
+/// Parses BDF font properties.
+pub fn parse_bdf(data: &[u8]) -> Option<Properties> {
+    if !is_bdf(data) {
+        return None;
+    }
+
+    let mut props = Properties::default();
+    for line in data.split(|b| *b == b'\n') {
+        let line = match core::str::from_utf8(line) {
+            Ok(line) => line.trim(),
+            Err(_) => continue,
+        };
+
+        let (name, value) = match line.find(char::is_whitespace) {
+            Some(pos) => (&line[..pos], line[pos..].trim()),
+            None => (line, ""),
+        };
+
+        match name {
+            // Glyphs follow properties.
+            "ENDPROPERTIES" | "CHARS" => break,
+            "PIXEL_SIZE" => props.pixel_size = value.parse().ok(),
+            // Unlike properties, `FONT` is not quoted.
+            "FONT" => props.set(name, value),
+            _ => {
+                if let Some(value) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    // Quotes inside strings are doubled.
+                    props.set(name, &value.replace("\"\"", "\""));
+                }
+            }
+        }
+    }
+
+    props.fill_from_xlfd();
+    Some(props)
+}
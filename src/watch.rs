@@ -0,0 +1,152 @@
+//! Font directories watching.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use notify::Watcher;
+
+use crate::ID;
+
+/// A font directories watcher.
+///
+/// Created via [`Database::watch`](crate::Database::watch).
+/// Watches all directories loaded via
+/// [`Database::load_fonts_dir`](crate::Database::load_fonts_dir),
+/// [`Database::load_fonts_dir_with`](crate::Database::load_fonts_dir_with)
+/// and [`Database::load_system_fonts`](crate::Database::load_system_fonts)
+/// before its creation.
+///
+/// The `Database` is never modified in the background. Changes are applied only via
+/// [`Database::apply_watched_changes`](crate::Database::apply_watched_changes).
+/// Directories are watched until the watcher is dropped.
+///
+/// Missing directories, like `~/.fonts`, are watched once they are created.
+pub struct FontWatcher {
+    state: Mutex<WatchState>,
+    pending: Arc<AtomicBool>,
+}
+
+struct WatchState {
+    watcher: notify::RecommendedWatcher,
+    /// Root directories that didn't exist yet, which parents are watched instead.
+    missing: Vec<PathBuf>,
+}
+
+impl core::fmt::Debug for FontWatcher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FontWatcher")
+            .field("has_changes", &self.has_changes())
+            .finish()
+    }
+}
+
+impl FontWatcher {
+    pub(crate) fn new<'a, I, F>(roots: I, mut on_change: F) -> Result<Self, std::io::Error>
+    where
+        I: IntoIterator<Item = &'a Path>,
+        F: FnMut() + Send + 'static,
+    {
+        let pending = Arc::new(AtomicBool::new(false));
+        let handler_pending = pending.clone();
+        let handler = move |event: notify::Result<notify::Event>| match event {
+            Ok(event) => {
+                if is_relevant(&event.kind) {
+                    handler_pending.store(true, Ordering::Release);
+                    on_change();
+                }
+            }
+            Err(e) => log::warn!("Failed to watch font directories cause {}.", e),
+        };
+
+        let mut watcher = notify::recommended_watcher(handler).map_err(into_io_error)?;
+        let mut missing = Vec::new();
+        for root in roots {
+            if root.is_dir() {
+                watcher
+                    .watch(root, notify::RecursiveMode::Recursive)
+                    .map_err(into_io_error)?;
+            } else if let Some(parent) = root.parent().filter(|p| p.is_dir()) {
+                // Wait for a missing directory to be created.
+                watcher
+                    .watch(parent, notify::RecursiveMode::NonRecursive)
+                    .map_err(into_io_error)?;
+                missing.push(root.to_path_buf());
+            }
+        }
+
+        Ok(FontWatcher {
+            state: Mutex::new(WatchState { watcher, missing }),
+            pending,
+        })
+    }
+
+    /// Checks that watched directories have changed since the last
+    /// [`Database::apply_watched_changes`](crate::Database::apply_watched_changes) call.
+    pub fn has_changes(&self) -> bool {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn take_changes(&self) -> bool {
+        let changed = self.pending.swap(false, Ordering::AcqRel);
+        if changed {
+            self.watch_created_roots();
+        }
+
+        changed
+    }
+
+    // Watches missing root directories that were created since.
+    //
+    // Cannot be done from the events handler, since the watcher is not reentrant.
+    fn watch_created_roots(&self) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(e) => e.into_inner(),
+        };
+
+        let WatchState { watcher, missing } = &mut *state;
+        missing.retain(|root| {
+            if !root.is_dir() {
+                return true;
+            }
+
+            match watcher.watch(root, notify::RecursiveMode::Recursive) {
+                Ok(()) => false,
+                Err(e) => {
+                    log::warn!("Failed to watch '{}' cause {}.", root.display(), e);
+                    true
+                }
+            }
+        });
+    }
+}
+
+/// A font face change applied by
+/// [`Database::apply_watched_changes`](crate::Database::apply_watched_changes).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FaceEvent {
+    /// A new font face was loaded.
+    Added(ID),
+    /// A font face was removed. Its ID is no longer valid.
+    Removed(ID),
+    /// Font face properties have changed, while its ID stayed the same.
+    Changed(ID),
+}
+
+fn is_relevant(kind: &notify::EventKind) -> bool {
+    match kind {
+        notify::EventKind::Access(_) => false,
+        // Metadata changes, like access time updates, do not affect fonts.
+        notify::EventKind::Modify(notify::event::ModifyKind::Metadata(_)) => false,
+        _ => true,
+    }
+}
+
+fn into_io_error(e: notify::Error) -> std::io::Error {
+    let message = e.to_string();
+    match e.kind {
+        notify::ErrorKind::Io(e) => e,
+        _ => std::io::Error::new(std::io::ErrorKind::Other, message),
+    }
+}
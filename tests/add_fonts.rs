@@ -13,3 +13,61 @@ fn add_fonts_and_get_ids_back() {
     let font = font_db.face(id).unwrap();
     assert!(font.families.iter().any(|(name, _)| name == "Tuffy"));
 }
+
+#[test]
+fn generic_family_fallback() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_TTF.to_vec());
+    font_db.set_sans_serif_families(["Missing Sans", "Tuffy", "Other Sans"]);
+
+    let names: Vec<_> = font_db.family_names(&fontdb::Family::SansSerif).collect();
+    assert_eq!(names, ["Missing Sans", "Tuffy", "Other Sans"]);
+    assert_eq!(
+        font_db.family_name(&fontdb::Family::SansSerif),
+        "Missing Sans"
+    );
+
+    let query = fontdb::Query {
+        families: &[fontdb::Family::SansSerif],
+        ..fontdb::Query::default()
+    };
+    assert!(font_db.query(&query).is_some());
+
+    font_db.set_sans_serif_family("Missing Sans");
+    assert!(font_db.query(&query).is_none());
+}
+
+#[derive(Default)]
+struct CountingProvider {
+    requests: std::sync::atomic::AtomicUsize,
+}
+
+impl fontdb::FontDataProvider for CountingProvider {
+    fn data(&self) -> Option<Arc<dyn AsRef<[u8]> + Sync + Send>> {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Some(Arc::new(DEMO_TTF))
+    }
+}
+
+#[test]
+fn custom_source() {
+    let provider = Arc::new(CountingProvider::default());
+    let requests = || provider.requests.load(std::sync::atomic::Ordering::SeqCst);
+
+    let mut font_db = fontdb::Database::new();
+    let ids = font_db.load_font_source(fontdb::Source::Custom(provider.clone()));
+    assert_eq!(ids.len(), 1);
+    assert_eq!(requests(), 1);
+
+    let font = font_db.face(ids[0]).unwrap();
+    assert!(font.families.iter().any(|(name, _)| name == "Tuffy"));
+
+    // The data is requested lazily.
+    let len = font_db.with_face_data(ids[0], |data, index| {
+        assert_eq!(index, 0);
+        data.len()
+    });
+    assert_eq!(len, Some(DEMO_TTF.len()));
+    assert_eq!(requests(), 2);
+}
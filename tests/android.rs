@@ -0,0 +1,107 @@
+use std::path::Path;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/android")
+        .join(name)
+}
+
+fn fonts_dir() -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts")
+}
+
+#[test]
+fn load_fonts_xml() {
+    let mut db = fontdb::Database::new();
+    let report = db
+        .load_android_fonts_xml(fixture("fonts.xml"), fonts_dir())
+        .unwrap();
+
+    let faces: Vec<_> = report
+        .faces
+        .iter()
+        .map(|id| {
+            let face = db.face(*id).unwrap();
+            (face.weight.0, face.style)
+        })
+        .collect();
+
+    let mut expected = vec![
+        (400, fontdb::Style::Normal),
+        (700, fontdb::Style::Normal),
+        (400, fontdb::Style::Italic),
+    ];
+    #[cfg(feature = "woff")]
+    expected.push((300, fontdb::Style::Normal));
+    expected.push((400, fontdb::Style::Normal));
+    assert_eq!(faces, expected);
+
+    // A missing file and a missing face index.
+    let mut errors: Vec<_> = report
+        .errors
+        .iter()
+        .map(|e| (e.path.clone().unwrap(), e.index))
+        .collect();
+    errors.retain(|(path, _)| !path.ends_with("Tuffy.woff"));
+    assert_eq!(
+        errors,
+        [
+            (fonts_dir().join("NotoSerif-Regular.ttf"), None),
+            (fonts_dir().join("Tuffy.ttf"), Some(1)),
+        ]
+    );
+
+    assert_eq!(report.files[0].0, fonts_dir().join("Tuffy.ttf"));
+    assert_eq!(report.files[0].1.len(), 4);
+
+    assert_eq!(db.family_name(&fontdb::Family::SansSerif), "Tuffy");
+    // Not loaded.
+    assert_eq!(db.family_name(&fontdb::Family::Serif), "Times New Roman");
+    #[cfg(feature = "woff")]
+    assert_eq!(db.family_name(&fontdb::Family::Monospace), "Tuffy");
+}
+
+#[test]
+fn load_legacy_system_fonts_xml() {
+    let mut db = fontdb::Database::new();
+    let report = db
+        .load_android_fonts_xml(fixture("system_fonts.xml"), fonts_dir())
+        .unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+    assert_eq!(db.family_name(&fontdb::Family::SansSerif), "Tuffy");
+}
+
+#[test]
+fn invalid_fonts_xml() {
+    let mut db = fontdb::Database::new();
+    let res = db.load_android_fonts_xml(fixture("missing.xml"), fonts_dir());
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+    let res = db.load_android_fonts_xml(fonts_dir().join("LICENSE.txt"), fonts_dir());
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn lang_fallback_families() {
+    let mut db = fontdb::Database::new();
+    db.load_android_fonts_xml(fixture("fonts.xml"), fonts_dir())
+        .unwrap();
+    db.set_sans_serif_family("Missing");
+
+    let mut query = fontdb::Query {
+        families: &[fontdb::Family::SansSerif],
+        ..fontdb::Query::default()
+    };
+    assert!(db.query(&query).is_none());
+
+    query.lang = Some("ja");
+    assert!(db.query(&query).is_none());
+
+    query.lang = Some("und-Arab");
+    assert!(db.query(&query).is_some());
+
+    // Only generic families are affected.
+    query.families = &[fontdb::Family::Name("Missing")];
+    assert!(db.query(&query).is_none());
+}
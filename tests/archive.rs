@@ -0,0 +1,103 @@
+use std::path::Path;
+
+mod common;
+
+use common::{make_dir, with_weight};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+fn check_archive(path: &Path, member: &str) {
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_archive(path);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.files[0].0, path.join(member));
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].0, path.join("README.txt"));
+    assert_eq!(report.skipped[0].1, fontdb::SkipReason::NotAFont);
+
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.post_script_name, "Tuffy");
+    match face.source {
+        fontdb::Source::Archive(ref archive, ref name, _) => {
+            assert_eq!(archive, path);
+            assert_eq!(name, member);
+        }
+        _ => panic!("an archive source expected"),
+    }
+
+    // The member is extracted again on access.
+    let data = db.with_face_data(report.faces[0], |data, _| data.to_vec());
+    assert_eq!(data.as_deref(), Some(DEMO_TTF));
+}
+
+#[test]
+fn zip() {
+    check_archive("tests/fonts/archive.zip".as_ref(), "fonts/Tuffy.ttf");
+}
+
+#[test]
+fn tar() {
+    let member = format!("fonts/{}Tuffy.ttf", "very-long-directory-name/".repeat(5));
+    check_archive("tests/fonts/archive.tar".as_ref(), &member);
+}
+
+#[test]
+fn not_an_archive() {
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_archive("tests/fonts/Tuffy.ttf");
+    assert!(db.is_empty());
+    assert_eq!(report.errors.len(), 1);
+
+    let report = db.load_fonts_archive("tests/fonts/missing.zip");
+    assert!(matches!(
+        report.errors[0].error,
+        fontdb::LoadError::IoError(_)
+    ));
+}
+
+/// Appends a regular file entry to a tar archive.
+fn append_tar_entry(archive: &mut Vec<u8>, name: &str, data: &[u8]) {
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    archive.extend_from_slice(&header);
+    archive.extend_from_slice(data);
+    archive.resize((archive.len() + 511) / 512 * 512, 0);
+}
+
+#[test]
+fn duplicate_names() {
+    let bold_ttf = with_weight(DEMO_TTF, 700);
+    let mut tar = Vec::new();
+    append_tar_entry(&mut tar, "font.ttf", DEMO_TTF);
+    append_tar_entry(&mut tar, "font.ttf", &bold_ttf);
+    tar.resize(tar.len() + 1024, 0);
+    let dir = make_dir("archive-duplicates", &[("fonts.tar", &tar)]);
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_archive(dir.join("fonts.tar"));
+    assert_eq!(report.faces.len(), 2);
+
+    // Each member is located by its offset, not by its name.
+    let data = db.with_face_data(report.faces[0], |data, _| data.to_vec());
+    assert_eq!(data.as_deref(), Some(DEMO_TTF));
+    let data = db.with_face_data(report.faces[1], |data, _| data.to_vec());
+    assert_eq!(data, Some(bold_ttf));
+    assert_eq!(
+        db.face(report.faces[1]).unwrap().weight,
+        fontdb::Weight::BOLD
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,315 @@
+mod common;
+
+use common::make_dir;
+
+const XLFD: &str = "-misc-fixed-bold-r-semicondensed--13-120-75-75-c-70-iso10646-1";
+
+/// Builds a PCF font with just a properties table.
+fn make_pcf(props: &[(&str, Result<&str, i32>)], big_endian: bool) -> Vec<u8> {
+    let u32_bytes = |n: u32| {
+        if big_endian {
+            n.to_be_bytes()
+        } else {
+            n.to_le_bytes()
+        }
+    };
+
+    let mut strings = Vec::new();
+    let mut add_string = |s: &str| {
+        let offset = strings.len() as u32;
+        strings.extend_from_slice(s.as_bytes());
+        strings.push(0);
+        offset
+    };
+
+    let mut records = Vec::new();
+    for (name, value) in props {
+        records.extend_from_slice(&u32_bytes(add_string(name)));
+        match value {
+            Ok(s) => {
+                records.push(1);
+                records.extend_from_slice(&u32_bytes(add_string(s)));
+            }
+            Err(n) => {
+                records.push(0);
+                records.extend_from_slice(&u32_bytes(*n as u32));
+            }
+        }
+    }
+
+    let format: u32 = if big_endian { 1 << 2 | 1 << 3 } else { 0 };
+    let mut table = format.to_le_bytes().to_vec();
+    table.extend_from_slice(&u32_bytes(props.len() as u32));
+    table.extend_from_slice(&records);
+    while (table.len() - 8) % 4 != 0 {
+        table.push(0);
+    }
+    table.extend_from_slice(&u32_bytes(strings.len() as u32));
+    table.extend_from_slice(&strings);
+
+    let mut data = b"\x01fcp".to_vec();
+    data.extend_from_slice(&1u32.to_le_bytes());
+    // Properties table type, format, size and offset.
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&format.to_le_bytes());
+    data.extend_from_slice(&(table.len() as u32).to_le_bytes());
+    data.extend_from_slice(&24u32.to_le_bytes());
+    data.extend_from_slice(&table);
+    data
+}
+
+fn make_bdf(properties: &str) -> Vec<u8> {
+    format!(
+        "STARTFONT 2.1\nFONT {}\nSIZE 13 75 75\nFONTBOUNDINGBOX 7 13 0 -2\n\
+         STARTPROPERTIES 2\n{}ENDPROPERTIES\nCHARS 1\nSTARTCHAR A\nENDCHAR\nENDFONT\n",
+        XLFD, properties
+    )
+    .into_bytes()
+}
+
+#[test]
+fn parse_pcf() {
+    let props = [
+        ("FONT", Ok(XLFD)),
+        ("FAMILY_NAME", Ok("Fixed")),
+        ("WEIGHT_NAME", Ok("Bold")),
+        ("SLANT", Ok("I")),
+        ("SPACING", Ok("C")),
+        ("PIXEL_SIZE", Err(13)),
+    ];
+
+    for big_endian in [false, true] {
+        let mut db = fontdb::Database::new();
+        let report = db.load_font_data(make_pcf(&props, big_endian));
+        assert!(report.is_ok());
+        assert_eq!(report.faces.len(), 1);
+
+        let face = db.face(report.faces[0]).unwrap();
+        assert_eq!(face.families[0].0, "Fixed");
+        assert_eq!(face.post_script_name, XLFD);
+        assert_eq!(face.weight, fontdb::Weight::BOLD);
+        assert_eq!(face.style, fontdb::Style::Italic);
+        // Taken from the font name.
+        assert_eq!(face.stretch, fontdb::Stretch::SemiCondensed);
+        assert!(face.monospaced);
+        assert_eq!(face.pixel_size, Some(13));
+        assert_eq!(face.format, fontdb::FaceFormat::Pcf);
+    }
+}
+
+#[test]
+fn parse_bdf() {
+    let mut db = fontdb::Database::new();
+    let data = make_bdf("FAMILY_NAME \"Fixed \"\"Misc\"\"\"\nPIXEL_SIZE 14\n");
+    let report = db.load_font_data(data);
+    assert!(report.is_ok());
+
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.families[0].0, "Fixed \"Misc\"");
+    assert_eq!(face.weight, fontdb::Weight::BOLD);
+    assert_eq!(face.style, fontdb::Style::Normal);
+    assert_eq!(face.stretch, fontdb::Stretch::SemiCondensed);
+    assert!(face.monospaced);
+    assert_eq!(face.pixel_size, Some(14));
+    assert_eq!(face.format, fontdb::FaceFormat::Bdf);
+
+    let query = fontdb::Query {
+        families: &[fontdb::Family::Name("Fixed \"Misc\"")],
+        weight: fontdb::Weight::BOLD,
+        ..fontdb::Query::default()
+    };
+    assert_eq!(db.query(&query), Some(report.faces[0]));
+}
+
+#[test]
+fn medium_is_regular() {
+    let props = [
+        ("FAMILY_NAME", Ok("Terminus")),
+        ("WEIGHT_NAME", Ok("Medium")),
+        ("SLANT", Ok("R")),
+        ("SPACING", Ok("P")),
+    ];
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_pcf(&props, false));
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.weight, fontdb::Weight::NORMAL);
+    assert!(!face.monospaced);
+    assert_eq!(face.pixel_size, None);
+
+    // A family name is required.
+    let report = db.load_font_data(make_pcf(&[("SLANT", Ok("R"))], false));
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+fn load_bitmap_dir() {
+    let dir = make_dir("bitmap", &[]);
+    std::fs::write(dir.join("fixed.bdf"), make_bdf("")).unwrap();
+    let props = [("FONT", Ok(XLFD))];
+    std::fs::write(dir.join("fixed.pcf"), make_pcf(&props, false)).unwrap();
+    // Aliases are not fonts.
+    std::fs::write(dir.join("fonts.alias"), "fixed -misc-fixed-*\n").unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+    for face in db.faces() {
+        assert_eq!(face.families[0].0, "fixed");
+        assert_eq!(face.pixel_size, Some(13));
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Builds a Windows FNT font without glyphs.
+fn make_fnt(
+    face: &str,
+    weight: u16,
+    italic: bool,
+    fixed_pitch: bool,
+    pixel_height: u16,
+) -> Vec<u8> {
+    const HEADER_SIZE: usize = 118;
+
+    let mut data = vec![0; HEADER_SIZE];
+    let size = (HEADER_SIZE + face.len() + 1) as u32;
+    data[0..2].copy_from_slice(&0x0200u16.to_le_bytes());
+    data[2..6].copy_from_slice(&size.to_le_bytes());
+    data[80] = italic as u8;
+    data[83..85].copy_from_slice(&weight.to_le_bytes());
+    data[88..90].copy_from_slice(&pixel_height.to_le_bytes());
+    data[90] = if fixed_pitch { 0x30 } else { 0x21 };
+    data[105..109].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+    data.extend_from_slice(face.as_bytes());
+    data.push(0);
+    data
+}
+
+/// Builds a Windows NE font library with the provided `FONT` resources.
+fn make_fon(fonts: &[Vec<u8>]) -> Vec<u8> {
+    const NE_OFFSET: usize = 64;
+    const SHIFT: u16 = 4;
+
+    let mut data = vec![0; NE_OFFSET];
+    data[0..2].copy_from_slice(b"MZ");
+    data[0x3C..0x40].copy_from_slice(&(NE_OFFSET as u32).to_le_bytes());
+
+    let mut ne = vec![0; 0x40];
+    ne[0..2].copy_from_slice(b"NE");
+    // The resource table directly follows the NE header.
+    ne[0x24..0x26].copy_from_slice(&0x40u16.to_le_bytes());
+    data.extend_from_slice(&ne);
+
+    let table_size = 2 + (8 + 12) + (8 + 12 * fonts.len()) + 2;
+    let align = |n: usize| (n + (1 << SHIFT) - 1) >> SHIFT << SHIFT;
+    let mut offset = align(data.len() + table_size);
+
+    let mut resources = Vec::new();
+    let mut entries = Vec::new();
+    for font in fonts {
+        let start = offset;
+        offset = align(offset + font.len());
+        entries.push((start, offset - start));
+        resources.resize(start - align(data.len() + table_size), 0);
+        resources.extend_from_slice(font);
+    }
+    resources.resize(offset - align(data.len() + table_size), 0);
+
+    data.extend_from_slice(&SHIFT.to_le_bytes());
+    // A font directory, which must be skipped.
+    data.extend_from_slice(&0x8007u16.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&[0; 4 + 12]);
+    data.extend_from_slice(&0x8008u16.to_le_bytes());
+    data.extend_from_slice(&(fonts.len() as u16).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    for (start, len) in entries {
+        data.extend_from_slice(&((start >> SHIFT) as u16).to_le_bytes());
+        data.extend_from_slice(&((len >> SHIFT) as u16).to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+    }
+    data.extend_from_slice(&0u16.to_le_bytes());
+
+    data.resize(align(data.len()), 0);
+    data.extend_from_slice(&resources);
+    data
+}
+
+#[test]
+fn parse_fon() {
+    let fonts = [
+        make_fnt("MS Sans Serif", 400, false, false, 13),
+        make_fnt("MS Sans Serif", 700, true, false, 16),
+    ];
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_fon(&fonts));
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.families[0].0, "MS Sans Serif");
+    assert_eq!(face.index, 0);
+    assert_eq!(face.weight, fontdb::Weight::NORMAL);
+    assert_eq!(face.style, fontdb::Style::Normal);
+    assert!(!face.monospaced);
+    assert_eq!(face.pixel_size, Some(13));
+    assert_eq!(face.format, fontdb::FaceFormat::WinFnt);
+
+    let face = db.face(report.faces[1]).unwrap();
+    assert_eq!(face.index, 1);
+    assert_eq!(face.weight, fontdb::Weight::BOLD);
+    assert_eq!(face.style, fontdb::Style::Italic);
+    assert_eq!(face.pixel_size, Some(16));
+
+    // Each face receives its own FNT resource.
+    let data = db.with_face_data(report.faces[1], |data, index| (data.to_vec(), index));
+    assert_eq!(data, Some((fonts[1].clone(), 0)));
+}
+
+#[test]
+fn parse_fnt() {
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_fnt("Terminal", 0, false, true, 12));
+    assert!(report.is_ok());
+
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.families[0].0, "Terminal");
+    assert_eq!(face.weight, fontdb::Weight::NORMAL);
+    assert!(face.monospaced);
+    assert_eq!(face.pixel_size, Some(12));
+
+    // A face name is required.
+    let report = db.load_font_data(make_fnt("", 400, false, true, 12));
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+fn load_winfnt_dir() {
+    let dir = make_dir("winfnt", &[]);
+    let font = make_fnt("Terminal", 400, false, true, 12);
+    std::fs::write(dir.join("terminal.fnt"), &font).unwrap();
+    std::fs::write(dir.join("terminal.fon"), make_fon(&[font])).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+    for face in db.faces() {
+        assert_eq!(face.families[0].0, "Terminal");
+        assert_eq!(face.pixel_size, Some(12));
+    }
+
+    // Both are recognized by their content as well, even though more data has to be read.
+    std::fs::rename(dir.join("terminal.fnt"), dir.join("terminal")).unwrap();
+    std::fs::rename(dir.join("terminal.fon"), dir.join("terminal.data")).unwrap();
+    let options = fontdb::ScanOptions::new().sniff_content(true);
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir_with(&dir, &options);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+mod common;
+
+use common::{make_dir, with_weight};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+// Renames cached family names, so cached faces could be told apart from parsed ones.
+fn patch_cache(path: &Path, from: &str, to: &str) {
+    assert_eq!(from.len(), to.len());
+    // Match only whole length-prefixed strings and not file paths.
+    let from = [&(from.len() as u32).to_le_bytes(), from.as_bytes()].concat();
+    let to = [&(to.len() as u32).to_le_bytes(), to.as_bytes()].concat();
+    let mut data = std::fs::read(path).unwrap();
+    let mut i = 0;
+    while i + from.len() <= data.len() {
+        if data[i..i + from.len()] == from[..] {
+            data[i..i + from.len()].copy_from_slice(&to);
+        }
+
+        i += 1;
+    }
+
+    std::fs::write(path, data).unwrap();
+}
+
+fn load(fonts_dir: &Path, cache_dir: &Path) -> fontdb::Database {
+    let mut db = fontdb::Database::new();
+    db.set_cache_dir(cache_dir);
+    let report = db.load_fonts_dir(fonts_dir);
+    assert!(report.errors.is_empty());
+    db
+}
+
+fn family(db: &fontdb::Database) -> String {
+    db.faces().next().unwrap().families[0].0.clone()
+}
+
+#[test]
+fn cache() {
+    let fonts_dir = make_dir("cache-fonts", &[]);
+    let cache_dir = make_dir("cache", &[]).join("nested");
+    std::fs::write(fonts_dir.join("Tuffy.ttf"), DEMO_TTF).unwrap();
+
+    let db = load(&fonts_dir, &cache_dir);
+    assert_eq!(db.len(), 1);
+    let files: Vec<_> = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    let cache_file = files[0].clone();
+    let cache_name = cache_file.file_name().unwrap().to_str().unwrap();
+    assert!(cache_name.contains(env!("CARGO_PKG_VERSION")));
+
+    // Caches written by other versions are left intact.
+    let other_file = cache_dir.join("faces-1-0.1.0.cache");
+    std::fs::write(&other_file, b"other").unwrap();
+    let parsed = db.faces().next().unwrap().clone();
+
+    // Cached faces are the same as parsed ones.
+    let db = load(&fonts_dir, &cache_dir);
+    let cached = db.faces().next().unwrap();
+    match (&cached.source, &parsed.source) {
+        (fontdb::Source::File(a), fontdb::Source::File(b)) => assert_eq!(a, b),
+        _ => panic!("file sources expected"),
+    }
+    assert_eq!(cached.index, parsed.index);
+    assert_eq!(cached.families, parsed.families);
+    assert_eq!(cached.post_script_name, parsed.post_script_name);
+    assert_eq!(cached.style, parsed.style);
+    assert_eq!(cached.weight, parsed.weight);
+    assert_eq!(cached.stretch, parsed.stretch);
+    assert_eq!(cached.monospaced, parsed.monospaced);
+
+    // Unchanged files are not parsed again.
+    patch_cache(&cache_file, "Tuffy", "Cachy");
+    let db = load(&fonts_dir, &cache_dir);
+    assert_eq!(family(&db), "Cachy");
+
+    // Changed files are parsed again.
+    std::fs::write(fonts_dir.join("Tuffy.ttf"), with_weight(DEMO_TTF, 700)).unwrap();
+    let db = load(&fonts_dir, &cache_dir);
+    assert_eq!(family(&db), "Tuffy");
+    assert_eq!(db.faces().next().unwrap().weight, fontdb::Weight::BOLD);
+    patch_cache(&cache_file, "Tuffy", "Cachy");
+    let db = load(&fonts_dir, &cache_dir);
+    assert_eq!(family(&db), "Cachy");
+    assert_eq!(db.faces().next().unwrap().weight, fontdb::Weight::BOLD);
+
+    // Caches with an unknown format are ignored.
+    let mut data = std::fs::read(&cache_file).unwrap();
+    data[8] = data[8].wrapping_add(1);
+    std::fs::write(&cache_file, data).unwrap();
+    let db = load(&fonts_dir, &cache_dir);
+    assert_eq!(family(&db), "Tuffy");
+
+    assert_eq!(std::fs::read(&other_file).unwrap(), b"other");
+
+    // Without a cache, files are always parsed.
+    patch_cache(&cache_file, "Tuffy", "Cachy");
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(&fonts_dir);
+    assert_eq!(family(&db), "Tuffy");
+
+    let _ = std::fs::remove_dir_all(&fonts_dir);
+    let _ = std::fs::remove_dir_all(cache_dir.parent().unwrap());
+}
@@ -0,0 +1,96 @@
+//! Helpers shared by integration tests.
+
+// Each test target uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::convert::TryInto;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Creates an empty temporary directory with the specified files.
+///
+/// Each call returns a new directory, even across concurrently running test processes.
+pub fn make_dir(name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!(
+        "fontdb-{}-{}-{}-{}",
+        name,
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, data) in files {
+        std::fs::write(dir.join(name), data).unwrap();
+    }
+
+    dir
+}
+
+/// Returns a font with a different `usWeightClass` and size.
+pub fn with_weight(data: &[u8], weight: u16) -> Vec<u8> {
+    let mut data = data.to_vec();
+    let num_tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+    for i in 0..num_tables {
+        let record = 12 + i * 16;
+        if &data[record..record + 4] == b"OS/2" {
+            let offset = u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap());
+            let offset = offset as usize + 4;
+            data[offset..offset + 2].copy_from_slice(&weight.to_be_bytes());
+        }
+    }
+
+    // A different size allows detecting changes regardless of the modification time precision.
+    data.extend_from_slice(&[0; 4]);
+    data
+}
+
+/// Returns files inside a directory recursively, in the `read_dir` order.
+///
+/// Which is the order fonts from a single directory are loaded in.
+pub fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walk_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+
+    files
+}
+
+/// Returns file paths and indices of faces loaded from files, in the loading order.
+pub fn face_files(db: &fontdb::Database) -> Vec<(PathBuf, u32)> {
+    db.faces()
+        .map(|face| match &face.source {
+            fontdb::Source::File(path) => (path.clone(), face.index),
+            _ => panic!("unexpected source"),
+        })
+        .collect()
+}
+
+/// Returns sorted file names of faces loaded from files.
+pub fn loaded_files(db: &fontdb::Database) -> Vec<String> {
+    let mut names: Vec<_> = db
+        .faces()
+        .filter_map(|face| match &face.source {
+            fontdb::Source::File(path) => Some(file_name(path)),
+            _ => None,
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap().to_string_lossy().into_owned()
+}
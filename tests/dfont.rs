@@ -0,0 +1,143 @@
+mod common;
+
+use common::{make_dir, with_weight};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+// Builds a resource fork with a `FOND` resource and the `sfnt` resources.
+fn make_dfont(fonts: &[&[u8]]) -> Vec<u8> {
+    let mut resources = Vec::new();
+    let mut offsets = Vec::new();
+    for font in std::iter::once(&b"not a font"[..]).chain(fonts.iter().copied()) {
+        offsets.push(resources.len() as u32);
+        resources.extend_from_slice(&(font.len() as u32).to_be_bytes());
+        resources.extend_from_slice(font);
+    }
+
+    let data_offset = 256u32;
+    let map_offset = data_offset + resources.len() as u32;
+
+    let mut map = vec![0; 16 + 4 + 2 + 2];
+    map.extend_from_slice(&28u16.to_be_bytes()); // type list offset
+    map.extend_from_slice(&0u16.to_be_bytes()); // name list offset
+    map.extend_from_slice(&1u16.to_be_bytes()); // types count - 1
+    let references_offset = 2 + 8 * 2;
+    map.extend_from_slice(b"FOND");
+    map.extend_from_slice(&0u16.to_be_bytes());
+    map.extend_from_slice(&(references_offset as u16).to_be_bytes());
+    map.extend_from_slice(b"sfnt");
+    map.extend_from_slice(&(fonts.len() as u16 - 1).to_be_bytes());
+    map.extend_from_slice(&(references_offset as u16 + 12).to_be_bytes());
+    for (id, offset) in offsets.iter().enumerate() {
+        map.extend_from_slice(&(id as u16 + 128).to_be_bytes());
+        map.extend_from_slice(&0xFFFFu16.to_be_bytes()); // no name
+        map.extend_from_slice(&offset.to_be_bytes()); // attributes + offset
+        map.extend_from_slice(&0u32.to_be_bytes());
+    }
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&data_offset.to_be_bytes());
+    header.extend_from_slice(&map_offset.to_be_bytes());
+    header.extend_from_slice(&(resources.len() as u32).to_be_bytes());
+    header.extend_from_slice(&(map.len() as u32).to_be_bytes());
+    map[..16].copy_from_slice(&header);
+
+    let mut data = header;
+    data.resize(data_offset as usize, 0);
+    data.extend_from_slice(&resources);
+    data.extend_from_slice(&map);
+    data
+}
+
+#[test]
+fn load_dfont() {
+    let bold = with_weight(DEMO_TTF, 700);
+    let data = make_dfont(&[DEMO_TTF, &bold]);
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(data);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+
+    let regular = db.face(report.faces[0]).unwrap();
+    assert_eq!(regular.index, 0);
+    assert_eq!(regular.post_script_name, "Tuffy");
+    assert_eq!(regular.weight, fontdb::Weight::MEDIUM);
+    let bold_face = db.face(report.faces[1]).unwrap();
+    assert_eq!(bold_face.index, 1);
+    assert_eq!(bold_face.weight, fontdb::Weight::BOLD);
+
+    // A face font is passed instead of the whole suitcase.
+    let data = db.with_face_data(report.faces[1], |data, index| (data.to_vec(), index));
+    assert_eq!(data, Some((bold, 0)));
+}
+
+#[test]
+fn load_dfont_dir() {
+    let dir = make_dir("dfont", &[]);
+    std::fs::write(dir.join("Tuffy.dfont"), make_dfont(&[DEMO_TTF])).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+
+    // Suitcases are recognized by content as well.
+    std::fs::rename(dir.join("Tuffy.dfont"), dir.join("Tuffy.data")).unwrap();
+    let options = fontdb::ScanOptions::new().sniff_content(true);
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir_with(&dir, &options);
+    assert_eq!(report.faces.len(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn nested_dfont() {
+    let data = make_dfont(&[&make_dfont(&[DEMO_TTF])]);
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(data);
+    assert!(db.is_empty());
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+fn huge_resource_length() {
+    let mut data = make_dfont(&[DEMO_TTF]);
+    // The `sfnt` resource follows the 14 bytes long `FOND` one.
+    data[256 + 14..256 + 18].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(data);
+    assert!(db.is_empty());
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[cfg(feature = "memmap")]
+#[test]
+fn shared_dfont_data() {
+    let bold = with_weight(DEMO_TTF, 700);
+    let dir = make_dir(
+        "dfont-shared",
+        &[("Tuffy.dfont", &make_dfont(&[DEMO_TTF, &bold]))],
+    );
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert_eq!(report.faces.len(), 2);
+
+    // Shared data is a face font, like the one passed by `with_face_data`.
+    let (data, index) = unsafe { db.make_shared_face_data(report.faces[1]) }.unwrap();
+    assert_eq!((*data).as_ref(), &bold[..]);
+    assert_eq!(index, 0);
+    let (data, index) = unsafe { db.make_shared_face_data(report.faces[0]) }.unwrap();
+    assert_eq!((*data).as_ref(), DEMO_TTF);
+    assert_eq!(index, 0);
+    assert!(matches!(
+        db.face(report.faces[0]).unwrap().source,
+        fontdb::Source::SharedFile(..)
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
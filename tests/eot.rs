@@ -0,0 +1,151 @@
+mod common;
+
+use common::make_dir;
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+const FLAG_COMPRESSED: u32 = 0x4;
+const FLAG_XOR_ENCRYPTED: u32 = 0x1000_0000;
+
+/// Builds a version 2.1 EOT font.
+fn make_eot(font_data: &[u8], flags: u32, weight: u32, italic: bool, names: [&str; 4]) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&[0; 4]); // EOT size
+    header.extend_from_slice(&(font_data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&0x0002_0001u32.to_le_bytes());
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&[2, 0, 5, 3, 0, 0, 0, 0, 0, 0]); // PANOSE
+    header.push(1); // charset
+    header.push(italic as u8);
+    header.extend_from_slice(&weight.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes()); // fsType
+    header.extend_from_slice(&0x504Cu16.to_le_bytes());
+    header.extend_from_slice(&[0; 4 * 4 + 4 * 2 + 4 + 4 * 4]);
+    for name in names.iter().chain(&[""]) {
+        let name: Vec<u8> = name.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+        header.extend_from_slice(&[0, 0]); // padding
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&name);
+    }
+
+    let mut font_data = font_data.to_vec();
+    if flags & FLAG_XOR_ENCRYPTED != 0 {
+        font_data.iter_mut().for_each(|b| *b ^= 0x50);
+    }
+
+    header.extend_from_slice(&font_data);
+    let size = header.len() as u32;
+    header[0..4].copy_from_slice(&size.to_le_bytes());
+    header
+}
+
+#[test]
+fn parse_eot() {
+    let names = [
+        "Tuffy Web",
+        "Bold Italic",
+        "Version 1.0",
+        "Tuffy Web Bold Italic",
+    ];
+    for flags in [0, FLAG_XOR_ENCRYPTED] {
+        let mut db = fontdb::Database::new();
+        let ids = db.load_font_source(fontdb::Source::Binary(std::sync::Arc::new(make_eot(
+            DEMO_TTF, flags, 700, true, names,
+        ))));
+        assert_eq!(ids.len(), 1);
+
+        // Names, style and weight are taken from the header.
+        let face = db.face(ids[0]).unwrap();
+        assert_eq!(face.families[0].0, "Tuffy Web");
+        assert!(face.families.iter().any(|(name, _)| name == "Tuffy"));
+        assert_eq!(face.post_script_name, "Tuffy");
+        assert_eq!(face.weight, fontdb::Weight::BOLD);
+        assert_eq!(face.style, fontdb::Style::Italic);
+        assert!(face.format.is_scalable_sfnt());
+
+        // The embedded font is returned.
+        let data = db.with_face_data(ids[0], |data, index| (data.to_vec(), index));
+        assert_eq!(data, Some((DEMO_TTF.to_vec(), 0)));
+
+        let query = fontdb::Query {
+            families: &[fontdb::Family::Name("Tuffy Web")],
+            weight: fontdb::Weight::BOLD,
+            style: fontdb::Style::Italic,
+            ..fontdb::Query::default()
+        };
+        assert_eq!(db.query(&query), Some(ids[0]));
+    }
+}
+
+#[test]
+fn oblique_eot() {
+    let names = ["Tuffy Web", "Oblique", "Version 1.0", "Tuffy Web Oblique"];
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_eot(DEMO_TTF, 0, 300, false, names));
+    assert!(report.is_ok());
+
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.weight, fontdb::Weight::LIGHT);
+    assert_eq!(face.style, fontdb::Style::Oblique);
+
+    // Binary data is decoded only once.
+    match face.source {
+        fontdb::Source::Binary(ref data) => assert_eq!((**data).as_ref(), DEMO_TTF),
+        _ => panic!("a binary source expected"),
+    }
+}
+
+#[test]
+fn compressed_eot() {
+    // MicroType Express data is not supported.
+    let names = ["Compressed", "Oblique", "Version 1.0", "Compressed Oblique"];
+    let data = make_eot(b"MTX data", FLAG_COMPRESSED, 300, false, names);
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(data);
+    assert!(db.is_empty());
+    assert!(matches!(
+        report.errors[0].error,
+        fontdb::LoadError::MalformedFont
+    ));
+}
+
+#[test]
+fn nested_eot() {
+    // The embedded font must not be another container.
+    let names = ["Tuffy", "Regular", "Version 1.0", "Tuffy"];
+    let nested = make_eot(DEMO_TTF, 0, 400, false, names);
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_eot(&nested, 0, 400, false, names));
+    assert!(db.is_empty());
+    assert!(matches!(
+        report.errors[0].error,
+        fontdb::LoadError::MalformedFont
+    ));
+}
+
+#[test]
+fn load_eot_dir() {
+    let dir = make_dir("eot", &[]);
+    let names = ["Tuffy", "Regular", "Version 1.0", "Tuffy"];
+    std::fs::write(
+        dir.join("tuffy.eot"),
+        make_eot(DEMO_TTF, 0, 400, false, names),
+    )
+    .unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+    assert_eq!(db.face(report.faces[0]).unwrap().families.len(), 1);
+
+    // Shared data is the embedded font as well.
+    #[cfg(feature = "memmap")]
+    {
+        let (data, index) = unsafe { db.make_shared_face_data(report.faces[0]) }.unwrap();
+        assert_eq!((*data).as_ref(), DEMO_TTF);
+        assert_eq!(index, 0);
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,397 @@
+use std::path::{Path, PathBuf};
+
+mod common;
+
+use common::{face_files, loaded_files, make_dir, walk_files};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+/// A fonts directory with `font.ttf`, `blocked/blocked.ttf` and `blocked/allowed.ttf`.
+fn make_fonts_dir(name: &str) -> PathBuf {
+    let dir = make_dir(name, &[]);
+    let fonts_dir = dir.join("fonts");
+    std::fs::create_dir_all(fonts_dir.join("blocked")).unwrap();
+    std::fs::write(fonts_dir.join("font.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(fonts_dir.join("blocked/blocked.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(fonts_dir.join("blocked/allowed.ttf"), DEMO_TTF).unwrap();
+    dir
+}
+
+/// Writes `fonts.conf` listing the `fonts` directory and loads it.
+fn load_config(dir: &Path, elements: &str) -> (fontdb::Database, fontdb::LoadReport) {
+    let config = format!(
+        "<?xml version=\"1.0\"?>\n\
+         <fontconfig>\n\
+           <dir>{}</dir>\n\
+           {}\n\
+         </fontconfig>\n",
+        dir.join("fonts").display(),
+        elements
+    );
+    let config_path = dir.join("fonts.conf");
+    std::fs::write(&config_path, config).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report =
+        db.load_fontconfig_with_vars(&[("FONTCONFIG_FILE", config_path.to_str().unwrap())]);
+    (db, report)
+}
+
+#[test]
+fn select_globs() {
+    let dir = make_fonts_dir("fontconfig-globs");
+    let (db, report) = load_config(
+        &dir,
+        "<selectfont>\
+           <rejectfont><glob>*/blocked/*</glob></rejectfont>\
+           <acceptfont><glob>*/allowed.ttf</glob></acceptfont>\
+         </selectfont>",
+    );
+    assert_eq!(loaded_files(&db), ["allowed.ttf", "font.ttf"]);
+    let config = db.system_font_config().unwrap();
+    assert_eq!(config.reject_globs, ["*/blocked/*"]);
+    assert_eq!(config.accept_globs, ["*/allowed.ttf"]);
+    assert!(config.errors.is_empty());
+    assert_eq!(
+        report.skipped,
+        [(
+            dir.join("fonts/blocked/blocked.ttf"),
+            fontdb::SkipReason::Rejected
+        )]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn select_patterns() {
+    let dir = make_fonts_dir("fontconfig-patterns");
+    let (db, report) = load_config(
+        &dir,
+        "<selectfont><rejectfont>\
+           <pattern><patelt name=\"family\"><string>tuffy</string></patelt></pattern>\
+         </rejectfont></selectfont>",
+    );
+    assert!(db.is_empty());
+    assert_eq!(report.skipped.len(), 3);
+
+    let (db, _) = load_config(
+        &dir,
+        &format!(
+            "<selectfont>\
+               <rejectfont>\
+                 <pattern>\
+                   <patelt name=\"family\"><string>Tuffy</string></patelt>\
+                   <patelt name=\"weight\"><const>medium</const></patelt>\
+                 </pattern>\
+               </rejectfont>\
+               <acceptfont>\
+                 <pattern><patelt name=\"file\"><string>{}</string></patelt></pattern>\
+               </acceptfont>\
+             </selectfont>",
+            dir.join("fonts/blocked/allowed.ttf").display()
+        ),
+    );
+    assert_eq!(loaded_files(&db), ["allowed.ttf"]);
+    let config = db.system_font_config().unwrap();
+    assert!(config.reject_globs.is_empty());
+    assert_eq!(
+        config.reject_patterns,
+        [[
+            ("family".to_string(), "Tuffy".to_string()),
+            ("weight".to_string(), "medium".to_string()),
+        ]]
+    );
+    assert_eq!(
+        config.accept_patterns,
+        [[(
+            "file".to_string(),
+            dir.join("fonts/blocked/allowed.ttf")
+                .to_string_lossy()
+                .into_owned()
+        )]]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn select_unsupported_properties() {
+    // Unsupported properties never match.
+    let dir = make_fonts_dir("fontconfig-unsupported");
+    let (db, _) = load_config(
+        &dir,
+        "<selectfont><rejectfont>\
+           <pattern><patelt name=\"lang\"><string>en</string></patelt></pattern>\
+         </rejectfont></selectfont>",
+    );
+    assert_eq!(db.len(), 3);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn aliases() {
+    let dir = make_fonts_dir("fontconfig-aliases");
+    let (db, _) = load_config(
+        &dir,
+        "<alias><family>sans-serif</family><default><family>Default Sans</family></default></alias>\n\
+         <alias><family>sans-serif</family><accept><family>First Accept</family></accept></alias>\n\
+         <alias>\
+           <family>sans-serif</family>\
+           <prefer><family>Missing Sans</family><family>Tuffy</family></prefer>\
+         </alias>\n\
+         <alias><family>sans serif</family><accept><family>Second Accept</family></accept></alias>\n\
+         <alias><family>sans-serif</family><prefer><family>tuffy</family></prefer></alias>",
+    );
+    let names: Vec<_> = db.family_names(&fontdb::Family::SansSerif).collect();
+    assert_eq!(
+        names,
+        [
+            "Missing Sans",
+            "Tuffy",
+            "Second Accept",
+            "First Accept",
+            "Default Sans"
+        ]
+    );
+    let query = fontdb::Query {
+        families: &[fontdb::Family::SansSerif],
+        ..fontdb::Query::default()
+    };
+    assert!(db.query(&query).is_some());
+    let config = db.system_font_config().unwrap();
+    assert_eq!(config.files, [dir.join("fonts.conf")]);
+    assert_eq!(config.dirs, [dir.join("fonts")]);
+    assert_eq!(config.aliases.len(), 1);
+    assert_eq!(config.aliases[0].0, "sans-serif");
+    assert_eq!(config.aliases[0].1.len(), 5);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn family_substitution() {
+    let dir = make_fonts_dir("fontconfig-substitution");
+    let (db, _) = load_config(
+        &dir,
+        "<match target=\"pattern\">\
+           <test name=\"family\"><string>Helvetica</string></test>\
+           <edit name=\"family\" mode=\"prepend\"><string>Tuffy</string></edit>\
+         </match>\n\
+         <match target=\"pattern\">\
+           <test name=\"lang\"><string>ja</string></test>\
+           <test name=\"family\"><string>Gothic</string></test>\
+           <edit name=\"family\" mode=\"assign\"><string>Tuffy</string></edit>\
+         </match>\n\
+         <match target=\"pattern\">\
+           <test name=\"lang\"><string>xx</string></test>\
+           <test name=\"family\"><string>Tuffy</string></test>\
+           <edit name=\"family\" mode=\"delete\"><string>Tuffy</string></edit>\
+         </match>",
+    );
+    let query = |families: &[fontdb::Family], lang: Option<&str>| {
+        db.query(&fontdb::Query {
+            families,
+            lang,
+            ..fontdb::Query::default()
+        })
+    };
+    assert!(query(&[fontdb::Family::Name("Helvetica")], None).is_some());
+    assert!(query(&[fontdb::Family::Name("Gothic")], Some("ja")).is_some());
+    assert!(query(&[fontdb::Family::Name("Gothic")], Some("en")).is_none());
+    assert!(query(&[fontdb::Family::Name("Gothic")], None).is_none());
+    assert!(query(&[fontdb::Family::Name("Tuffy")], None).is_some());
+    assert!(query(&[fontdb::Family::Name("Tuffy")], Some("xx")).is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn family_substitution_without_families() {
+    let dir = make_fonts_dir("fontconfig-substitution-empty");
+    let (db, _) = load_config(
+        &dir,
+        "<match target=\"pattern\">\
+           <test name=\"family\"><string>Helvetica</string></test>\
+           <edit name=\"family\" mode=\"delete_all\"><string>Helvetica</string></edit>\
+           <edit name=\"family\" mode=\"assign\"><string>Arial</string></edit>\
+           <edit name=\"family\" mode=\"delete\"><string>Arial</string></edit>\
+         </match>\n\
+         <match target=\"pattern\">\
+           <test qual=\"all\" name=\"family\" compare=\"not_eq\"><string>Arial</string></test>\
+           <edit name=\"family\" mode=\"assign\"><string>Tuffy</string></edit>\
+         </match>",
+    );
+    let query = |families: &[fontdb::Family]| {
+        db.query(&fontdb::Query {
+            families,
+            ..fontdb::Query::default()
+        })
+    };
+    // An empty families list passes the `all` and `not_eq` test.
+    assert!(query(&[]).is_some());
+    // Edits after `delete_all` leave the list empty again.
+    assert!(query(&[fontdb::Family::Name("Helvetica")]).is_some());
+    assert!(query(&[fontdb::Family::Name("Arial")]).is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn config_discovery() {
+    let dir = make_dir("fontconfig-discovery", &[]);
+    let sysroot = dir.join("root");
+    let conf_d = sysroot.join("conf/conf.d");
+    std::fs::create_dir_all(&conf_d).unwrap();
+    std::fs::create_dir_all(sysroot.join("fonts")).unwrap();
+    std::fs::create_dir_all(sysroot.join("extra")).unwrap();
+    std::fs::create_dir_all(sysroot.join("xdg/fonts")).unwrap();
+    std::fs::write(sysroot.join("fonts/fonts.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(sysroot.join("extra/extra.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(sysroot.join("xdg/fonts/xdg.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(
+        sysroot.join("conf/fonts.conf"),
+        "<fontconfig>\
+           <dir>/fonts</dir>\
+           <include ignore_missing=\"yes\">conf.d</include>\
+           <include ignore_missing=\"yes\">missing.d</include>\
+         </fontconfig>",
+    )
+    .unwrap();
+    std::fs::write(
+        conf_d.join("10-relative.conf"),
+        "<fontconfig><dir prefix=\"relative\">../../extra</dir></fontconfig>",
+    )
+    .unwrap();
+    std::fs::write(
+        conf_d.join("20-xdg.conf"),
+        "<fontconfig><dir prefix=\"xdg\">fonts</dir></fontconfig>",
+    )
+    .unwrap();
+    std::fs::write(conf_d.join("30-broken.conf"), "<fontconfig>").unwrap();
+    // Must be ignored, since not named like `NN-name.conf`.
+    std::fs::write(conf_d.join("README"), "not a config").unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fontconfig_with_vars(&[
+        ("FONTCONFIG_SYSROOT", sysroot.to_str().unwrap()),
+        ("FONTCONFIG_PATH", "/conf"),
+        ("XDG_DATA_HOME", "/xdg"),
+    ]);
+    assert_eq!(loaded_files(&db), ["extra.ttf", "fonts.ttf", "xdg.ttf"]);
+    assert!(report.errors.is_empty());
+    let config = db.system_font_config().unwrap();
+    assert_eq!(
+        config.files,
+        [
+            sysroot.join("conf/fonts.conf"),
+            conf_d.join("10-relative.conf"),
+            conf_d.join("20-xdg.conf"),
+        ]
+    );
+    assert_eq!(
+        config.dirs,
+        [
+            sysroot.join("fonts"),
+            conf_d.join("../../extra"),
+            sysroot.join("xdg/fonts"),
+        ]
+    );
+    assert_eq!(config.errors.len(), 1);
+    assert_eq!(config.errors[0].0, conf_d.join("30-broken.conf"));
+    assert!(fontdb::Database::new().system_font_config().is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_config() {
+    let dir = make_dir("fontconfig-missing", &[]);
+    let config_path = dir.join("missing.conf");
+    let mut db = fontdb::Database::new();
+    db.load_fontconfig_with_vars(&[("FONTCONFIG_FILE", config_path.to_str().unwrap())]);
+    assert!(db.is_empty());
+    let config = db.system_font_config().unwrap();
+    assert!(config.files.is_empty());
+    assert_eq!(config.errors.len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn roots_order() {
+    let dir = make_dir("fontconfig-roots", &[]);
+    let roots: Vec<_> = (0..4).map(|i| dir.join(format!("root{}", i))).collect();
+    for (i, root) in roots.iter().enumerate() {
+        // Roots of different size are walked at different speed when in parallel.
+        for j in 0..(4 - i) * 4 {
+            let sub_dir = root.join(format!("sub{}", j % 2));
+            std::fs::create_dir_all(&sub_dir).unwrap();
+            std::fs::write(sub_dir.join(format!("font{}.ttf", j)), DEMO_TTF).unwrap();
+        }
+    }
+    let missing = dir.join("missing");
+
+    let mut config = String::from("<fontconfig>");
+    for root in roots
+        .iter()
+        .take(2)
+        .chain([&missing])
+        .chain(roots.iter().skip(2))
+    {
+        config.push_str(&format!("<dir>{}</dir>", root.display()));
+    }
+    config.push_str("</fontconfig>");
+    let config_path = dir.join("fonts.conf");
+    std::fs::write(&config_path, config).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report =
+        db.load_fontconfig_with_vars(&[("FONTCONFIG_FILE", config_path.to_str().unwrap())]);
+
+    // Faces are loaded root by root, like when roots are walked serially.
+    let expected: Vec<_> = roots
+        .iter()
+        .flat_map(|root| walk_files(root))
+        .map(|path| (path, 0))
+        .collect();
+    assert_eq!(expected.len(), 40);
+    assert_eq!(face_files(&db), expected);
+
+    let scanned: Vec<_> = report.roots.iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        scanned,
+        [&roots[0], &roots[1], &missing, &roots[2], &roots[3]]
+    );
+    assert_eq!(report.unreadable_dirs.len(), 1);
+    assert_eq!(report.unreadable_dirs[0].0, missing);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// `load_system_fonts` reads the process environment, unlike the tests above.
+#[test]
+fn load_system_fonts() {
+    let dir = make_fonts_dir("fontconfig-system");
+    std::fs::write(
+        dir.join("fonts.conf"),
+        format!(
+            "<fontconfig><dir>{}</dir></fontconfig>",
+            dir.join("fonts").display()
+        ),
+    )
+    .unwrap();
+    std::env::set_var("FONTCONFIG_FILE", dir.join("fonts.conf"));
+
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    assert_eq!(db.len(), 3);
+    assert_eq!(
+        db.system_font_config().unwrap().files,
+        [dir.join("fonts.conf")]
+    );
+
+    std::env::remove_var("FONTCONFIG_FILE");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+mod common;
+
+use common::make_dir;
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+const DEMO_TTF_GZ: &[u8] = include_bytes!("./fonts/Tuffy.ttf.gz");
+
+#[test]
+fn load_gzip_file() {
+    let path = Path::new("tests/fonts/Tuffy.ttf.gz");
+    let mut db = fontdb::Database::new();
+    let ids = db.load_font_file(path).unwrap();
+    assert_eq!(ids.len(), 1);
+
+    // The original path is retained, while the data is decompressed only once.
+    let face = db.face(ids[0]).unwrap();
+    assert_eq!(face.post_script_name, "Tuffy");
+    match face.source {
+        fontdb::Source::SharedFile(ref face_path, ref data) => {
+            assert_eq!(face_path, path);
+            assert_eq!((**data).as_ref(), DEMO_TTF);
+        }
+        _ => panic!("a shared file source expected"),
+    }
+
+    let data = db.with_face_data(ids[0], |data, _| data.to_vec());
+    assert_eq!(data.as_deref(), Some(DEMO_TTF));
+}
+
+#[test]
+fn load_gzip_data() {
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(DEMO_TTF_GZ.to_vec());
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+
+    // Binary data is decompressed only once.
+    match db.face(report.faces[0]).unwrap().source {
+        fontdb::Source::Binary(ref data) => assert_eq!((**data).as_ref(), DEMO_TTF),
+        _ => panic!("a binary source expected"),
+    }
+
+    // A truncated stream.
+    let report = db.load_font_data(DEMO_TTF_GZ[..DEMO_TTF_GZ.len() / 2].to_vec());
+    assert!(matches!(
+        report.errors[0].error,
+        fontdb::LoadError::MalformedFont
+    ));
+}
+
+#[test]
+fn load_gzip_dir() {
+    let dir = make_dir("gzip", &[]);
+    std::fs::write(dir.join("Tuffy.ttf.gz"), DEMO_TTF_GZ).unwrap();
+    std::fs::write(dir.join("Tuffy.gz"), DEMO_TTF_GZ).unwrap();
+    std::fs::write(dir.join("README.txt.gz"), DEMO_TTF_GZ).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+    assert_eq!(report.files[0].0, dir.join("Tuffy.ttf.gz"));
+
+    let mut skipped = report.skipped.clone();
+    skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        skipped,
+        [
+            (
+                dir.join("README.txt.gz"),
+                fontdb::SkipReason::DeniedExtension
+            ),
+            (dir.join("Tuffy.gz"), fontdb::SkipReason::UnknownExtension),
+        ]
+    );
+
+    // Compressed files without an inner extension are recognized by their content.
+    let mut db = fontdb::Database::new();
+    let options = fontdb::ScanOptions::new().sniff_content(true);
+    let report = db.load_fonts_dir_with(&dir, &options);
+    assert_eq!(report.faces.len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Compresses data into a gzip stream using stored deflate blocks.
+fn make_gzip(data: &[u8]) -> Vec<u8> {
+    let mut gzip = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF];
+    let mut chunks = data.chunks(0xFFFF).peekable();
+    while let Some(chunk) = chunks.next() {
+        gzip.push(chunks.peek().is_none() as u8);
+        gzip.extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+        gzip.extend_from_slice(&(!(chunk.len() as u16)).to_le_bytes());
+        gzip.extend_from_slice(chunk);
+    }
+
+    // The CRC is not checked.
+    gzip.extend_from_slice(&0u32.to_le_bytes());
+    gzip.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    gzip
+}
+
+#[test]
+fn decompress_once() {
+    let dir = make_dir("gzip-once", &[("Tuffy.ttf.gz", DEMO_TTF_GZ)]);
+    let mut db = fontdb::Database::new();
+    db.set_cache_dir(dir.join("cache"));
+    let options = fontdb::ScanOptions::new().track_changes(true);
+    let report = db.load_fonts_dir_with(&dir, &options);
+    assert_eq!(report.faces.len(), 1);
+
+    // The file is not read again on access.
+    std::fs::write(dir.join("Tuffy.ttf.gz"), b"not a gzip file").unwrap();
+    for _ in 0..2 {
+        let data = db.with_face_data(report.faces[0], |data, _| data.to_vec());
+        assert_eq!(data.as_deref(), Some(DEMO_TTF));
+    }
+
+    // Unchanged faces still receive new data on rescan.
+    let padded = [DEMO_TTF, &[0; 4]].concat();
+    std::fs::write(dir.join("Tuffy.ttf.gz"), make_gzip(&padded)).unwrap();
+    let rescan = db.rescan();
+    assert!(rescan.changed.is_empty());
+    let data = db.with_face_data(report.faces[0], |data, _| data.to_vec());
+    assert_eq!(data, Some(padded));
+
+    // Compressed files are not cached, since they have to be decompressed anyway.
+    let mut db = fontdb::Database::new();
+    db.set_cache_dir(dir.join("cache"));
+    let report = db.load_fonts_dir(&dir);
+    assert!(matches!(
+        db.face(report.faces[0]).unwrap().source,
+        fontdb::Source::SharedFile(..)
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(all(feature = "memmap", feature = "woff"))]
+#[test]
+fn unshare_gzip_data() {
+    let woff = make_gzip(include_bytes!("./fonts/Tuffy.woff"));
+    let dir = make_dir("gzip-unshare", &[("Tuffy.woff.gz", &woff)]);
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert_eq!(report.faces.len(), 1);
+    let id = report.faces[0];
+    let data = db.with_face_data(id, |data, _| data.to_vec()).unwrap();
+
+    let (shared, _) = unsafe { db.make_shared_face_data(id) }.unwrap();
+    assert_eq!((*shared).as_ref(), &data[..]);
+
+    // Decompressed data is kept in memory, instead of reading the compressed file again.
+    db.make_face_data_unshared(id);
+    assert!(matches!(
+        db.face(id).unwrap().source,
+        fontdb::Source::SharedFile(..)
+    ));
+    assert_eq!(db.with_face_data(id, |data, _| data.to_vec()), Some(data));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use common::{face_files, make_dir, walk_files};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+#[test]
+fn load_font_data_report() {
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(DEMO_TTF.to_vec());
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 1);
+    assert!(db.face(report.faces[0]).is_some());
+
+    let report = db.load_font_data(b"\x00\x01\x00\x00 not a font".to_vec());
+    assert!(!report.is_ok());
+    assert!(report.faces.is_empty());
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].path, None);
+    assert_eq!(report.errors[0].index, Some(0));
+    assert!(matches!(
+        report.errors[0].error,
+        fontdb::LoadError::MalformedFont
+    ));
+}
+
+#[test]
+fn load_fonts_dir_report() {
+    let dir = make_dir(
+        "report",
+        &[
+            ("font.ttf", DEMO_TTF),
+            ("broken.ttf", b"not a font"),
+            ("readme.txt", b"text"),
+            ("font", DEMO_TTF),
+        ],
+    );
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(!report.is_ok());
+
+    assert_eq!(report.faces.len(), 1);
+    assert_eq!(db.len(), 1);
+
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].path, Some(dir.join("broken.ttf")));
+    assert_eq!(report.errors[0].index, Some(0));
+
+    assert_eq!(report.files.len(), 2);
+    let (path, ids) = report
+        .files
+        .iter()
+        .find(|(path, _)| path.ends_with("font.ttf"))
+        .unwrap();
+    assert_eq!(*path, dir.join("font.ttf"));
+    assert_eq!(ids.as_slice(), report.faces.as_slice());
+
+    let mut skipped = report.skipped.clone();
+    skipped.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        skipped,
+        [
+            (dir.join("font"), fontdb::SkipReason::UnknownExtension),
+            (dir.join("readme.txt"), fontdb::SkipReason::DeniedExtension),
+        ]
+    );
+
+    assert!(report.unreadable_dirs.is_empty());
+    assert_eq!(report.roots.len(), 1);
+    assert_eq!(report.roots[0].0, dir);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unreadable_dir() {
+    let parent = make_dir("unreadable", &[]);
+    let dir = parent.join("missing");
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(!report.is_ok());
+    assert_eq!(report.unreadable_dirs.len(), 1);
+    assert_eq!(report.unreadable_dirs[0].0, dir);
+
+    std::fs::remove_dir_all(&parent).unwrap();
+}
+
+#[test]
+fn load_font_file_ids() {
+    let dir = make_dir("file-ids", &[("font.ttf", DEMO_TTF)]);
+
+    let mut db = fontdb::Database::new();
+    let ids = db.load_font_file(dir.join("font.ttf")).unwrap();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(db.face(ids[0]).unwrap().post_script_name, "Tuffy");
+
+    db.remove_face(ids[0]);
+    assert!(db.is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn loading_order() {
+    let files: Vec<_> = (0..16)
+        .map(|i| (format!("font{}.ttf", i), DEMO_TTF))
+        .collect();
+    let files: Vec<_> = files.iter().map(|(n, d)| (n.as_str(), *d)).collect();
+    let dir = make_dir("order", &files);
+
+    let mut db1 = fontdb::Database::new();
+    let report1 = db1.load_fonts_dir(&dir);
+    let mut db2 = fontdb::Database::new();
+    let report2 = db2.load_fonts_dir(&dir);
+
+    let paths1: Vec<_> = report1.files.iter().map(|(path, _)| path).collect();
+    let paths2: Vec<_> = report2.files.iter().map(|(path, _)| path).collect();
+    assert_eq!(paths1, paths2);
+
+    // Faces are inserted in the files order.
+    let ids: Vec<_> = report1
+        .files
+        .iter()
+        .flat_map(|(_, ids)| ids.iter().copied())
+        .collect();
+    assert_eq!(ids, report1.faces);
+    assert_eq!(db1.faces().map(|f| f.id).collect::<Vec<_>>(), report1.faces);
+
+    for (face, (path, _)) in db1.faces().zip(report1.files.iter()) {
+        match &face.source {
+            fontdb::Source::File(face_path) => assert_eq!(face_path, path),
+            _ => panic!("unexpected source"),
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn same_order_as_serial_loading() {
+    // Files of different size and format are parsed at different speed when in parallel.
+    let dir = make_dir("serial-order", &[]);
+    for i in 0..32 {
+        let (data, ext): (&[u8], _) = if i % 3 == 0 {
+            (include_bytes!("./fonts/Tuffy.woff"), "woff")
+        } else {
+            (DEMO_TTF, "ttf")
+        };
+        let sub_dir = dir.join(format!("dir{}/sub{}", i % 4, i % 2));
+        std::fs::create_dir_all(&sub_dir).unwrap();
+        std::fs::write(sub_dir.join(format!("font{:02}.{}", i, ext)), data).unwrap();
+    }
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert_eq!(report.faces.len(), 32);
+
+    // Serial loading walks directories depth-first in the `read_dir` order.
+    let expected: Vec<_> = walk_files(&dir).into_iter().map(|path| (path, 0)).collect();
+    assert_eq!(face_files(&db), expected);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
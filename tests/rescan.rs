@@ -0,0 +1,100 @@
+mod common;
+
+use common::{make_dir, with_weight};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+#[test]
+fn rescan() {
+    let dir = make_dir("rescan", &[]);
+    std::fs::write(dir.join("unchanged.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join("changed.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join("deleted.ttf"), DEMO_TTF).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir_with(&dir, &fontdb::ScanOptions::new().track_changes(true));
+    assert_eq!(db.len(), 3);
+    let id = |name: &str| {
+        report
+            .files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .unwrap()
+            .1[0]
+    };
+    let unchanged_id = id("unchanged.ttf");
+    let changed_id = id("changed.ttf");
+    let deleted_id = id("deleted.ttf");
+
+    // Nothing has changed.
+    let report = db.rescan();
+    assert!(report.added.is_empty());
+    assert!(report.removed.is_empty());
+    assert!(report.changed.is_empty());
+    assert!(report.load.files.is_empty());
+
+    std::fs::write(dir.join("changed.ttf"), with_weight(DEMO_TTF, 700)).unwrap();
+    std::fs::remove_file(dir.join("deleted.ttf")).unwrap();
+    std::fs::write(dir.join("added.ttf"), DEMO_TTF).unwrap();
+
+    let report = db.rescan();
+    assert!(report.load.is_ok());
+    assert_eq!(report.added.len(), 1);
+    assert_eq!(report.removed, [deleted_id]);
+    assert_eq!(report.changed, [changed_id]);
+    assert_eq!(db.len(), 3);
+    assert!(db.face(deleted_id).is_none());
+    assert!(db.face(unchanged_id).is_some());
+    assert_eq!(db.face(changed_id).unwrap().weight, fontdb::Weight::BOLD);
+    assert!(db.face(report.added[0]).is_some());
+
+    // A changed file with the same faces keeps IDs and is not reported.
+    std::fs::write(dir.join("unchanged.ttf"), with_weight(DEMO_TTF, 500)).unwrap();
+    std::fs::write(dir.join("changed.ttf"), b"not a font").unwrap();
+    let report = db.rescan();
+    assert!(report.added.is_empty());
+    assert_eq!(report.removed, [changed_id]);
+    assert!(report.changed.is_empty());
+    assert_eq!(report.load.files.len(), 2);
+    assert_eq!(report.load.errors.len(), 1);
+    assert!(db.face(unchanged_id).is_some());
+    assert_eq!(db.len(), 2);
+
+    // Faces loaded via other methods are not affected.
+    let other = make_dir("rescan-other", &[]);
+    std::fs::write(other.join("other.ttf"), DEMO_TTF).unwrap();
+    let other_ids = db.load_font_file(other.join("other.ttf")).unwrap();
+    std::fs::remove_dir_all(&other).unwrap();
+    db.rescan();
+    assert!(db.face(other_ids[0]).is_some());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    let report = db.rescan();
+    assert_eq!(report.removed.len(), 2);
+    assert_eq!(report.load.unreadable_dirs.len(), 1);
+    assert_eq!(db.len(), 1);
+}
+
+#[test]
+fn rescan_without_tracking() {
+    let dir = make_dir("rescan-untracked", &[]);
+    std::fs::write(dir.join("font.ttf"), DEMO_TTF).unwrap();
+
+    let mut db = fontdb::Database::new();
+    let ids = db.load_fonts_dir(&dir).faces;
+
+    // Files are parsed again, but unchanged faces are not reported.
+    let report = db.rescan();
+    assert_eq!(report.load.files.len(), 1);
+    assert!(report.added.is_empty());
+    assert!(report.removed.is_empty());
+    assert!(report.changed.is_empty());
+    assert!(db.face(ids[0]).is_some());
+
+    std::fs::write(dir.join("font.ttf"), with_weight(DEMO_TTF, 700)).unwrap();
+    let report = db.rescan();
+    assert_eq!(report.changed, ids.as_slice());
+    assert_eq!(db.face(ids[0]).unwrap().weight, fontdb::Weight::BOLD);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+mod common;
+
+use common::{loaded_files, make_dir};
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+#[test]
+fn extensions_are_case_insensitive() {
+    let dir = make_dir("case", &[("Font.Ttf", DEMO_TTF), ("font", DEMO_TTF)]);
+
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(&dir);
+    assert_eq!(loaded_files(&db), ["Font.Ttf"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sniff_content() {
+    let dir = make_dir(
+        "sniff",
+        &[
+            ("Font.Ttf", DEMO_TTF),
+            ("font", DEMO_TTF),
+            ("font.dfont", DEMO_TTF),
+            ("readme", b"Not a font."),
+            ("font.txt", DEMO_TTF),
+        ],
+    );
+
+    let mut db = fontdb::Database::new();
+    db.set_scan_options(fontdb::ScanOptions::new().sniff_content(true));
+    db.load_fonts_dir(&dir);
+    assert_eq!(loaded_files(&db), ["Font.Ttf", "font", "font.dfont"]);
+
+    let mut db = fontdb::Database::new();
+    db.set_scan_options(
+        fontdb::ScanOptions::new()
+            .sniff_content(true)
+            .deny_extension("DFONT")
+            .allow_extension("txt"),
+    );
+    db.load_fonts_dir(&dir);
+    assert_eq!(loaded_files(&db), ["Font.Ttf", "font", "font.txt"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn make_tree(name: &str) -> PathBuf {
+    let dir = make_dir(name, &[("root.ttf", DEMO_TTF)]);
+    for sub in ["a", "a/b", ".hidden", "skip"] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    std::fs::write(dir.join("a/a.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join("a/b/b.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join("a/b/b.otf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join(".hidden/hidden.ttf"), DEMO_TTF).unwrap();
+    std::fs::write(dir.join("skip/skip.ttf"), DEMO_TTF).unwrap();
+    dir
+}
+
+#[test]
+fn max_depth() {
+    let dir = make_tree("depth");
+    let mut db = fontdb::Database::new();
+
+    db.load_fonts_dir_with(&dir, &fontdb::ScanOptions::new().max_depth(0));
+    assert_eq!(loaded_files(&db), ["root.ttf"]);
+
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir_with(&dir, &fontdb::ScanOptions::new().max_depth(1));
+    assert_eq!(
+        loaded_files(&db),
+        ["a.ttf", "hidden.ttf", "root.ttf", "skip.ttf"]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn hidden_dirs_and_excludes() {
+    let dir = make_tree("excludes");
+
+    let options = fontdb::ScanOptions::new()
+        .skip_hidden_dirs(true)
+        .exclude("skip")
+        .exclude("a/**/*.otf");
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir_with(&dir, &options);
+    assert_eq!(loaded_files(&db), ["a.ttf", "b.ttf", "root.ttf"]);
+    assert_eq!(
+        report.skipped,
+        [(dir.join("a/b/b.otf"), fontdb::SkipReason::Excluded)]
+    );
+
+    // Database options are not affected.
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(&dir);
+    assert_eq!(db.len(), 6);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn exclude_any_depth() {
+    let dir = make_dir("exclude-any-depth", &[("root.ttf", DEMO_TTF)]);
+    for sub in ["node_modules", "a/node_modules", "a/b/node_modules"] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+        std::fs::write(dir.join(sub).join("font.otf"), DEMO_TTF).unwrap();
+    }
+
+    // `**/` matches zero directories too.
+    let options = fontdb::ScanOptions::new().exclude("**/node_modules");
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir_with(&dir, &options);
+    assert_eq!(loaded_files(&db), ["root.ttf"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn extensions_set() {
+    let dir = make_tree("extensions-set");
+
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir_with(&dir, &fontdb::ScanOptions::new().extensions(["OTF"]));
+    assert_eq!(loaded_files(&db), ["b.otf"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks() {
+    let dir = make_dir("symlinks", &[("root.ttf", DEMO_TTF)]);
+    std::fs::create_dir_all(dir.join("excluded")).unwrap();
+    std::fs::write(dir.join("excluded/inside.ttf"), DEMO_TTF).unwrap();
+    std::os::unix::fs::symlink(dir.join("excluded"), dir.join("inside")).unwrap();
+
+    let outside = make_dir("symlinks-outside", &[("outside.ttf", DEMO_TTF)]);
+    std::os::unix::fs::symlink(&outside, dir.join("outside")).unwrap();
+
+    let load = |policy| {
+        let options = fontdb::ScanOptions::new()
+            .symlinks(policy)
+            .exclude("excluded");
+        let mut db = fontdb::Database::new();
+        db.load_fonts_dir_with(&dir, &options);
+        loaded_files(&db)
+    };
+
+    assert_eq!(
+        load(fontdb::SymlinkPolicy::Follow),
+        ["inside.ttf", "outside.ttf", "root.ttf"]
+    );
+    assert_eq!(
+        load(fontdb::SymlinkPolicy::WithinRoot),
+        ["inside.ttf", "root.ttf"]
+    );
+    assert_eq!(load(fontdb::SymlinkPolicy::Skip), ["root.ttf"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_dir_all(&outside).unwrap();
+}
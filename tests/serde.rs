@@ -0,0 +1,88 @@
+use fontdb::{Family, OwnedFamily, OwnedQuery, Query, Source, Stretch, Style, Weight};
+
+#[test]
+fn face_info_round_trip() {
+    let mut db = fontdb::Database::new();
+    db.load_font_file("tests/fonts/Tuffy.ttf").unwrap();
+    let face = db.faces().next().unwrap();
+
+    let json = serde_json::to_string(face).unwrap();
+    let restored: fontdb::FaceInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.families, face.families);
+    assert_eq!(restored.post_script_name, face.post_script_name);
+    assert_eq!(restored.style, face.style);
+    assert_eq!(restored.weight, face.weight);
+    assert_eq!(restored.stretch, face.stretch);
+    assert_eq!(restored.monospaced, face.monospaced);
+    match restored.source {
+        Source::File(ref path) => assert!(path.ends_with("Tuffy.ttf")),
+        _ => panic!("a file source expected"),
+    }
+
+    // Deserialized faces can be pushed into another database.
+    let mut db = fontdb::Database::new();
+    let id = db.push_face_info(restored);
+    assert_eq!(db.face(id).unwrap().post_script_name, "Tuffy");
+    assert!(db.with_face_data(id, |data, _| data.len()).is_some());
+}
+
+#[test]
+fn binary_source_round_trip() {
+    let data = std::fs::read("tests/fonts/Tuffy.ttf").unwrap();
+    let mut db = fontdb::Database::new();
+    db.load_font_data(data.clone());
+    let face = db.faces().next().unwrap();
+
+    let json = serde_json::to_string(face).unwrap();
+    let restored: fontdb::FaceInfo = serde_json::from_str(&json).unwrap();
+    match restored.source {
+        Source::Binary(ref restored) => assert_eq!((**restored).as_ref(), &data[..]),
+        _ => panic!("a binary source expected"),
+    }
+}
+
+#[test]
+fn properties() {
+    assert_eq!(serde_json::to_string(&Weight::BOLD).unwrap(), "700");
+    assert_eq!(serde_json::to_string(&Style::Italic).unwrap(), "\"Italic\"");
+    assert_eq!(
+        serde_json::to_string(&Stretch::Condensed).unwrap(),
+        "\"Condensed\""
+    );
+    let language: fontdb::Language = serde_json::from_str("\"English_UnitedStates\"").unwrap();
+    assert_eq!(language, fontdb::Language::English_UnitedStates);
+}
+
+#[test]
+fn owned_query() {
+    let families = [Family::Name("Tuffy"), Family::SansSerif];
+    let query = Query {
+        families: &families,
+        weight: Weight::BOLD,
+        lang: Some("en"),
+        ..Query::default()
+    };
+
+    let owned = OwnedQuery::from(&query);
+    let json = serde_json::to_string(&owned).unwrap();
+    let restored: OwnedQuery = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, owned);
+    assert_eq!(
+        restored.families,
+        [
+            OwnedFamily::Name("Tuffy".to_string()),
+            OwnedFamily::SansSerif
+        ]
+    );
+    restored.with_query(|q| assert_eq!(*q, query));
+
+    // Missing fields use default values.
+    let restored: OwnedQuery = serde_json::from_str(r#"{"families":["Serif"]}"#).unwrap();
+    assert_eq!(restored.families, [OwnedFamily::Serif]);
+    assert_eq!(restored.weight, Weight::NORMAL);
+
+    let mut db = fontdb::Database::new();
+    db.load_font_file("tests/fonts/Tuffy.ttf").unwrap();
+    let id = owned.with_query(|q| db.query(q));
+    assert!(id.is_some());
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::make_dir;
+
+const HEADER: &str = r#"%!PS-AdobeFont-1.0: Utopia-BoldItalic 001.002
+%%CreationDate: Wed Oct 02 18:58:16 1991
+11 dict begin
+/FontInfo 10 dict dup begin
+/version (001.002) readonly def
+/Notice (Copyright \(c\) 1989, 1991 Adobe Systems Incorporated.) readonly def
+/FullName (Utopia Bold Italic) readonly def
+/FamilyName (Utopia) readonly def
+/Weight (Bold) readonly def
+/ItalicAngle -13 def
+/isFixedPitch false def
+end readonly def
+/FontName /Utopia-BoldItalic def
+/PaintType 0 def
+/FontType 1 def
+currentdict end
+currentfile eexec
+"#;
+
+fn make_pfa(header: &str) -> Vec<u8> {
+    let mut data = header.as_bytes().to_vec();
+    data.extend_from_slice(b"d9d66f633b846a989b9974b0179fc6cc\n");
+    data
+}
+
+fn make_pfb(header: &str) -> Vec<u8> {
+    let mut data = vec![0x80, 1];
+    data.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    data.extend_from_slice(header.as_bytes());
+    // An encrypted segment, which may contain anything.
+    let encrypted = b"/FontName /Garbage def";
+    data.extend_from_slice(&[0x80, 2]);
+    data.extend_from_slice(&(encrypted.len() as u32).to_le_bytes());
+    data.extend_from_slice(encrypted);
+    data.extend_from_slice(&[0x80, 3]);
+    data
+}
+
+#[test]
+fn parse_type1() {
+    for data in [make_pfa(HEADER), make_pfb(HEADER)] {
+        let mut db = fontdb::Database::new();
+        let report = db.load_font_data(data.clone());
+        assert!(report.is_ok());
+        assert_eq!(report.faces.len(), 1);
+
+        let face = db.face(report.faces[0]).unwrap();
+        assert_eq!(face.families[0].0, "Utopia");
+        assert_eq!(face.post_script_name, "Utopia-BoldItalic");
+        assert_eq!(face.weight, fontdb::Weight::BOLD);
+        assert_eq!(face.style, fontdb::Style::Italic);
+        assert!(!face.monospaced);
+        assert_eq!(face.format, fontdb::FaceFormat::Type1);
+        assert!(!face.format.is_scalable_sfnt());
+
+        // The raw file is returned.
+        let face_data = db.with_face_data(report.faces[0], |data, _| data.to_vec());
+        assert_eq!(face_data, Some(data));
+
+        let query = fontdb::Query {
+            families: &[fontdb::Family::Name("Utopia")],
+            weight: fontdb::Weight::BOLD,
+            style: fontdb::Style::Italic,
+            ..fontdb::Query::default()
+        };
+        assert_eq!(db.query(&query), Some(report.faces[0]));
+    }
+}
+
+#[test]
+fn minimal_type1() {
+    // Properties are inferred from the font name, when `FontInfo` is missing.
+    let header = "%!FontType1-1.0: Courier\n/FontName /Courier-Oblique def\n\
+                  /FontInfo 2 dict dup begin /ItalicAngle -12 def /isFixedPitch true def end\n\
+                  /FullName (Courier Oblique) def\ncurrentfile eexec\n";
+    let mut db = fontdb::Database::new();
+    let report = db.load_font_data(make_pfa(header));
+    let face = db.face(report.faces[0]).unwrap();
+    assert_eq!(face.families[0].0, "Courier");
+    assert_eq!(face.style, fontdb::Style::Oblique);
+    assert_eq!(face.weight, fontdb::Weight::NORMAL);
+    assert!(face.monospaced);
+
+    // `FontName` is required.
+    let report = db.load_font_data(make_pfa("%!FontType1-1.0: Test\ncurrentfile eexec\n"));
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+fn load_type1_dir() {
+    let dir = make_dir("type1", &[]);
+    std::fs::write(dir.join("utopia.pfa"), make_pfa(HEADER)).unwrap();
+    std::fs::write(dir.join("utopia.pfb"), make_pfb(HEADER)).unwrap();
+    // Metrics are not fonts.
+    std::fs::write(dir.join("utopia.afm"), "StartFontMetrics 2.0\n").unwrap();
+
+    let mut db = fontdb::Database::new();
+    let report = db.load_fonts_dir(&dir);
+    assert!(report.is_ok());
+    assert_eq!(report.faces.len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::make_dir;
+
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+
+// A single file change may produce multiple events, therefore apply them until we get any faces.
+fn wait_for_events(
+    db: &mut fontdb::Database,
+    watcher: &fontdb::FontWatcher,
+) -> Vec<fontdb::FaceEvent> {
+    let now = Instant::now();
+    loop {
+        assert!(
+            now.elapsed() < Duration::from_secs(10),
+            "no changes detected"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+
+        let events = db.apply_watched_changes(watcher);
+        if !events.is_empty() {
+            return events;
+        }
+    }
+}
+
+#[test]
+fn watch() {
+    let dir = make_dir("watch", &[]);
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(&dir);
+    assert!(db.is_empty());
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let watcher = db
+        .watch(move || {
+            let _ = sender.send(());
+        })
+        .unwrap();
+    assert!(!watcher.has_changes());
+    assert!(db.apply_watched_changes(&watcher).is_empty());
+
+    std::fs::write(dir.join("font.ttf"), DEMO_TTF).unwrap();
+    let events = wait_for_events(&mut db, &watcher);
+    assert!(receiver.try_recv().is_ok());
+    assert_eq!(events.len(), 1);
+    let id = match events[0] {
+        fontdb::FaceEvent::Added(id) => id,
+        _ => panic!("unexpected event"),
+    };
+    assert!(db.face(id).is_some());
+
+    std::fs::remove_file(dir.join("font.ttf")).unwrap();
+    assert_eq!(
+        wait_for_events(&mut db, &watcher),
+        [fontdb::FaceEvent::Removed(id)]
+    );
+    assert!(db.is_empty());
+
+    drop(watcher);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watch_created_dir() {
+    let parent = make_dir("watch-created", &[]);
+    let dir = parent.join("fonts");
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(&dir);
+
+    let watcher = db.watch(|| {}).unwrap();
+    std::fs::create_dir(&dir).unwrap();
+    let now = Instant::now();
+    while !watcher.has_changes() {
+        assert!(
+            now.elapsed() < Duration::from_secs(10),
+            "no changes detected"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    // The directory is still empty.
+    assert!(db.apply_watched_changes(&watcher).is_empty());
+
+    // Files in the created directory must be detected as well.
+    std::fs::write(dir.join("font.ttf"), DEMO_TTF).unwrap();
+    let events = wait_for_events(&mut db, &watcher);
+    assert!(matches!(events[..], [fontdb::FaceEvent::Added(_)]));
+    assert_eq!(db.len(), 1);
+
+    drop(watcher);
+    std::fs::remove_dir_all(&parent).unwrap();
+}
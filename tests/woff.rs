@@ -0,0 +1,40 @@
+const DEMO_TTF: &[u8] = include_bytes!("./fonts/Tuffy.ttf");
+const DEMO_WOFF: &[u8] = include_bytes!("./fonts/Tuffy.woff");
+
+#[test]
+fn load_woff_data() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_WOFF.to_vec());
+    assert_eq!(font_db.len(), 1);
+
+    let face = font_db.faces().next().unwrap();
+    assert!(face.families.iter().any(|(name, _)| name == "Tuffy"));
+    assert_eq!(face.post_script_name, "Tuffy");
+
+    // WOFF data must be unpacked into a plain TrueType font.
+    let data = font_db
+        .with_face_data(face.id, |data, _| data.to_vec())
+        .unwrap();
+    assert_eq!(&data[0..4], &DEMO_TTF[0..4]);
+    assert_eq!(data.len(), DEMO_TTF.len());
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn load_woff_file() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts/Tuffy.woff");
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_file(&path).unwrap();
+    assert_eq!(font_db.len(), 1);
+
+    let id = font_db.faces().next().unwrap().id;
+    let magic = font_db.with_face_data(id, |data, _| data[0..4].to_vec());
+    assert_eq!(magic.as_deref(), Some(&DEMO_TTF[0..4]));
+}
+
+#[test]
+fn malformed_woff() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_WOFF[..1000].to_vec());
+    assert!(font_db.is_empty());
+}
@@ -0,0 +1,120 @@
+const DEMO_WOFF2: &[u8] = include_bytes!("./fonts/SourceCodePro-It.woff2");
+
+#[test]
+fn load_woff2_data() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_WOFF2.to_vec());
+    assert_eq!(font_db.len(), 1);
+
+    let face = font_db.faces().next().unwrap();
+    assert!(face
+        .families
+        .iter()
+        .any(|(name, _)| name == "Source Code Pro"));
+    assert_eq!(face.style, fontdb::Style::Italic);
+    assert!(face.monospaced);
+}
+
+#[test]
+fn reconstructed_glyphs() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_WOFF2.to_vec());
+    let id = font_db.faces().next().unwrap().id;
+
+    font_db
+        .with_face_data(id, |data, index| {
+            let face = ttf_parser::Face::parse(data, index).unwrap();
+            assert!(face.number_of_glyphs() > 1);
+
+            let mut outlined = 0;
+            for id in 0..face.number_of_glyphs() {
+                let mut builder = Builder(0);
+                let id = ttf_parser::GlyphId(id);
+                if let Some(bbox) = face.outline_glyph(id, &mut builder) {
+                    assert!(builder.0 > 0);
+                    assert!(bbox.x_min <= bbox.x_max && bbox.y_min <= bbox.y_max);
+                    outlined += 1;
+                }
+                // hmtx must be present for all glyphs.
+                assert!(face.glyph_hor_advance(id).is_some());
+            }
+
+            assert!(outlined > 100);
+        })
+        .unwrap();
+}
+
+#[test]
+fn malformed_woff2() {
+    let mut font_db = fontdb::Database::new();
+    font_db.load_font_data(DEMO_WOFF2[..DEMO_WOFF2.len() / 2].to_vec());
+    assert!(font_db.is_empty());
+}
+
+struct Builder(usize);
+
+impl ttf_parser::OutlineBuilder for Builder {
+    fn move_to(&mut self, _: f32, _: f32) {
+        self.0 += 1;
+    }
+
+    fn line_to(&mut self, _: f32, _: f32) {
+        self.0 += 1;
+    }
+
+    fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {
+        self.0 += 1;
+    }
+
+    fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {
+        self.0 += 1;
+    }
+
+    fn close(&mut self) {}
+}
+
+#[test]
+fn huge_table_length() {
+    // A header with 16 tables claiming 4 GiB each and a tiny compressed stream.
+    let num_tables = 16u16;
+    let mut data = Vec::new();
+    data.extend_from_slice(b"wOF2");
+    data.extend_from_slice(&0x00010000u32.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes()); // length
+    data.extend_from_slice(&num_tables.to_be_bytes());
+    data.extend_from_slice(&0u16.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes()); // totalSfntSize
+    data.extend_from_slice(&4u32.to_be_bytes()); // totalCompressedSize
+    data.resize(48, 0);
+    for _ in 0..num_tables {
+        // `name` without a transform and a UIntBase128 0xFFFFFFFF.
+        data.extend_from_slice(&[5, 0x8F, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+    data.extend_from_slice(&[0x21, 0x00, 0x00, 0x00]);
+
+    let mut font_db = fontdb::Database::new();
+    let report = font_db.load_font_data(data);
+    assert!(font_db.is_empty());
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+fn huge_compressed_size() {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"wOF2");
+    data.extend_from_slice(&0x00010000u32.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes()); // length
+    data.extend_from_slice(&1u16.to_be_bytes()); // numTables
+    data.extend_from_slice(&0u16.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes()); // totalSfntSize
+    data.extend_from_slice(&u32::MAX.to_be_bytes()); // totalCompressedSize
+    data.resize(48, 0);
+    // `name` without a transform and a length of 4.
+    data.extend_from_slice(&[5, 4]);
+    data.extend_from_slice(&[0x21, 0x00, 0x00, 0x00]);
+
+    let mut font_db = fontdb::Database::new();
+    let report = font_db.load_font_data(data);
+    assert!(font_db.is_empty());
+    assert_eq!(report.errors.len(), 1);
+}